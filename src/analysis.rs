@@ -1,4 +1,6 @@
+mod higher_order_invariant;
 mod send_sync_variance;
+mod uninit_exposure;
 mod unsafe_dataflow;
 mod unsafe_destructor;
 
@@ -6,9 +8,17 @@ use snafu::{Error, ErrorCompat};
 
 use crate::report::ReportLevel;
 
-pub use send_sync_variance::{BehaviorFlag as SendSyncBehaviorFlag, SendSyncVarianceChecker};
-pub use unsafe_dataflow::{BehaviorFlag as UnsafeDataflowBehaviorFlag, UnsafeDataflowChecker};
-pub use unsafe_destructor::UnsafeDestructorChecker;
+pub use higher_order_invariant::HigherOrderInvariantChecker;
+pub use send_sync_variance::{
+    BehaviorFlag as SendSyncBehaviorFlag, SendSyncMode, SendSyncVarianceChecker,
+};
+pub use uninit_exposure::UninitExposureChecker;
+pub use unsafe_dataflow::{
+    BehaviorFlag as UnsafeDataflowBehaviorFlag, UnsafeDataflowChecker, UnsafeDataflowMode,
+};
+pub use unsafe_destructor::{
+    BehaviorFlag as UnsafeDestructorBehaviorFlag, UnsafeDestructorChecker,
+};
 
 pub type AnalysisResult<'tcx, T> = Result<T, Box<dyn AnalysisError + 'tcx>>;
 
@@ -62,9 +72,11 @@ pub enum AnalysisErrorKind {
 
 #[derive(Debug, Copy, Clone)]
 pub enum AnalysisKind {
-    UnsafeDestructor,
+    UnsafeDestructor(UnsafeDestructorBehaviorFlag),
     SendSyncVariance(SendSyncBehaviorFlag),
     UnsafeDataflow(UnsafeDataflowBehaviorFlag),
+    UninitExposure,
+    HigherOrderInvariant,
 }
 
 trait IntoReportLevel {
@@ -74,7 +86,31 @@ trait IntoReportLevel {
 impl Into<Cow<'static, str>> for AnalysisKind {
     fn into(self) -> Cow<'static, str> {
         match &self {
-            AnalysisKind::UnsafeDestructor => "UnsafeDestructor".into(),
+            AnalysisKind::UnsafeDestructor(behaviors) => {
+                let mut v = vec!["UnsafeDestructor:"];
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::MOVED_READ) {
+                    v.push("MovedRead")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::DOUBLE_DROP) {
+                    v.push("DoubleDrop")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::UNINIT_READ) {
+                    v.push("UninitRead")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::UNINIT_DROP) {
+                    v.push("UninitDrop")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::GENERIC_AFTER_FREE) {
+                    v.push("GenericAfterFree")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::PUB_FIELD_FREE) {
+                    v.push("PubFieldFree")
+                }
+                if behaviors.contains(UnsafeDestructorBehaviorFlag::MAY_DANGLE_ACCESS) {
+                    v.push("MayDangleAccess")
+                }
+                v.join("/").into()
+            }
             AnalysisKind::SendSyncVariance(sv_analyses) => {
                 let mut v = vec!["SendSyncVariance:"];
                 if sv_analyses.contains(SendSyncBehaviorFlag::API_SEND_FOR_SYNC) {
@@ -98,6 +134,15 @@ impl Into<Cow<'static, str>> for AnalysisKind {
                 if sv_analyses.contains(SendSyncBehaviorFlag::RELAX_SYNC) {
                     v.push("RelaxSync")
                 }
+                if sv_analyses.contains(SendSyncBehaviorFlag::PTR_LIKE_SEND_FOR_SEND) {
+                    v.push("PtrLikeSendForSend")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::NON_SEND_SYNC_FIELD) {
+                    v.push("NonSendSyncField")
+                }
+                if sv_analyses.contains(SendSyncBehaviorFlag::TRAIT_CONTRACT) {
+                    v.push("TraitContract")
+                }
                 v.join("/").into()
             }
             AnalysisKind::UnsafeDataflow(bypass_kinds) => {
@@ -129,8 +174,16 @@ impl Into<Cow<'static, str>> for AnalysisKind {
                 if bypass_kinds.contains(UnsafeDataflowBehaviorFlag::VEC_SET_LEN) {
                     v.push("VecSetLen")
                 }
+                if bypass_kinds.contains(UnsafeDataflowBehaviorFlag::UNINIT) {
+                    v.push("Uninit")
+                }
+                if bypass_kinds.contains(UnsafeDataflowBehaviorFlag::USER_DEFINED) {
+                    v.push("UserDefined")
+                }
                 v.join("/").into()
             }
+            AnalysisKind::UninitExposure => "UninitExposure".into(),
+            AnalysisKind::HigherOrderInvariant => "HigherOrderInvariant".into(),
         }
     }
 }
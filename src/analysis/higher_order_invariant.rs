@@ -0,0 +1,276 @@
+//! Higher-order invariant detector
+//!
+//! Unsafe code must not trust the results of safe trait methods provided by the user
+//! (`ExactSizeIterator::len()`, `Iterator::size_hint()`, `Read::read()`, ...),
+//! since a safe implementation is free to return any value.
+//! This analyzer reports values returned by such methods that flow into
+//! a length or an index argument of a lifetime bypass without being clamped.
+use rustc_hir::BodyId;
+use rustc_middle::mir::{BinOp, Operand, Place, Rvalue, StatementKind};
+use rustc_middle::ty::{Instance, InstanceDef, ParamEnv};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+
+use crate::analysis::AnalysisKind;
+use crate::graph::Graph;
+use crate::prelude::*;
+use crate::report::{Report, ReportLevel, SpanRole};
+use crate::{ir, paths::*, utils};
+
+/// Bypasses that trust one of their arguments: (path, argument index, argument role)
+const INVARIANT_SINKS: &[(&[&str], usize, &str)] = &[
+    (&VEC_SET_LEN, 1, "length"),
+    (&VEC_FROM_RAW_PARTS, 1, "length"),
+    (&VEC_FROM_RAW_PARTS, 2, "capacity"),
+    (&SLICE_GET_UNCHECKED, 1, "index"),
+    (&SLICE_GET_UNCHECKED_MUT, 1, "index"),
+    (&SLICE_FROM_RAW_PARTS, 1, "length"),
+    (&SLICE_FROM_RAW_PARTS_MUT, 1, "length"),
+    (&PTR_SLICE_FROM_RAW_PARTS, 1, "length"),
+    (&PTR_SLICE_FROM_RAW_PARTS_MUT, 1, "length"),
+    (&INTRINSICS_COPY, 2, "count"),
+    (&INTRINSICS_COPY_NONOVERLAPPING, 2, "count"),
+];
+
+#[derive(Debug, Snafu)]
+pub enum HigherOrderInvariantError {
+    ResolveError { backtrace: Backtrace },
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for HigherOrderInvariantError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use HigherOrderInvariantError::*;
+        match self {
+            ResolveError { .. } => AnalysisErrorKind::OutOfScope,
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+/// A value from a user-provided trait method used as an argument of a bypass
+struct InvariantViolation {
+    method: String,
+    bypass: String,
+    role: &'static str,
+    source_span: Span,
+    sink_span: Span,
+}
+
+pub struct HigherOrderInvariantChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> HigherOrderInvariantChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        HigherOrderInvariantChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        if ReportLevel::Warning < self.rcx.report_level() {
+            return;
+        }
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            for violation in self.analyze_body(body_id) {
+                if self.rcx.is_suppressed(
+                    "higher_order_invariant",
+                    hir_map.body_owner_def_id(body_id).to_def_id(),
+                ) {
+                    continue;
+                }
+
+                let mut color_span = unwrap_or!(
+                    utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                );
+                color_span.add_sub_span(SpanRole::UntrustedValue, violation.source_span);
+                color_span.add_sub_span(SpanRole::Sink, violation.sink_span);
+
+                rudra_report(Report::with_color_span(
+                    tcx,
+                    ReportLevel::Warning,
+                    AnalysisKind::HigherOrderInvariant,
+                    format!(
+                        "Value returned by `{}` is trusted as the {} of `{}` in `{}`",
+                        violation.method,
+                        violation.role,
+                        violation.bypass,
+                        tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                    ),
+                    hir_map.body_owner_def_id(body_id).to_def_id(),
+                    &color_span,
+                ))
+            }
+        }
+    }
+
+    fn analyze_body(&self, body_id: BodyId) -> Vec<InvariantViolation> {
+        let tcx = self.rcx.tcx();
+        let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+        let translated_body = self.rcx.translate_body(body_did);
+        let body = match translated_body.as_ref() {
+            Ok(body) => body,
+            Err(e) => {
+                // MIR is not available for def - log it and continue
+                e.log();
+                return Vec::new();
+            }
+        };
+
+        let taint = self.collect_taint(body, tcx.param_env(body_did));
+        if taint.iter().all(Option::is_none) {
+            return Vec::new();
+        }
+
+        let ext = tcx.ext();
+        let mut violations = Vec::new();
+        for (id, terminator) in body.terminators().enumerate() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                ref args,
+                ..
+            } = terminator.kind
+            {
+                for &(path, arg_idx, role) in INVARIANT_SINKS {
+                    if !ext.match_def_path(callee_did, path) {
+                        continue;
+                    }
+                    let source = args
+                        .get(arg_idx)
+                        .and_then(Operand::place)
+                        .and_then(|place| taint[place.local.index()]);
+                    if let Some(source) = source {
+                        if !body.reaches(source, id) {
+                            continue;
+                        }
+                        let source_terminator = &body.basic_blocks[source].terminator;
+                        let method = match source_terminator.kind {
+                            ir::TerminatorKind::StaticCall { callee_did, .. } => {
+                                tcx.def_path_str(callee_did)
+                            }
+                            _ => continue,
+                        };
+                        violations.push(InvariantViolation {
+                            method,
+                            bypass: tcx.def_path_str(callee_did),
+                            role,
+                            source_span: source_terminator.original.source_info.span,
+                            sink_span: terminator.original.source_info.span,
+                        });
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    /// Maps each local to the block of the untrusted call its value is derived from.
+    fn collect_taint(
+        &self,
+        body: &ir::Body<'tcx>,
+        param_env: ParamEnv<'tcx>,
+    ) -> Vec<Option<usize>> {
+        let tcx = self.rcx.tcx();
+        let ext = tcx.ext();
+
+        let mut taint = vec![None; body.local_decls.len()];
+        for (id, terminator) in body.terminators().enumerate() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                callee_substs,
+                destination: Some((place, _)),
+                ..
+            } = terminator.kind
+            {
+                if tcx.trait_of_item(callee_did).is_none() {
+                    continue;
+                }
+                // Only the implementations that are not known statically are user-provided
+                let user_provided =
+                    match Instance::resolve(tcx, param_env, callee_did, callee_substs) {
+                        Err(_e) => {
+                            log_err!(ResolveError);
+                            false
+                        }
+                        Ok(Some(instance)) => matches!(instance.def, InstanceDef::Virtual(..)),
+                        Ok(None) => true,
+                    };
+                if user_provided {
+                    taint[place.local.index()].get_or_insert(id);
+                }
+            }
+        }
+
+        // Propagates taint until a fixed point, ignoring the control flow.
+        // Only the operations that keep the value a length are followed;
+        // e.g. `Vec::with_capacity(len)` is not tainted, and neither is its `len()`.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in body.basic_blocks.iter() {
+                for statement in block.statements.iter() {
+                    if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                        let source = match rvalue {
+                            Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => {
+                                operand_taint(&taint, operand)
+                            }
+                            Rvalue::BinaryOp(bin_op, box (lhs_op, rhs_op))
+                            | Rvalue::CheckedBinaryOp(bin_op, box (lhs_op, rhs_op))
+                                if is_arithmetic(*bin_op) =>
+                            {
+                                operand_taint(&taint, lhs_op)
+                                    .or_else(|| operand_taint(&taint, rhs_op))
+                            }
+                            _ => None,
+                        };
+                        if let (Some(source), None) = (source, taint[lhs.local.index()]) {
+                            taint[lhs.local.index()] = Some(source);
+                            changed = true;
+                        }
+                    }
+                }
+
+                // `?` unwraps the untrusted value through `Try::branch()`
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    destination: Some((place, _)),
+                    ..
+                } = block.terminator.kind
+                {
+                    if !ext.match_def_path(callee_did, &TRY_BRANCH) {
+                        continue;
+                    }
+                    let source = args
+                        .iter()
+                        .find_map(|operand| operand_taint(&taint, operand));
+                    if let (Some(source), None) = (source, taint[place.local.index()]) {
+                        taint[place.local.index()] = Some(source);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        taint
+    }
+}
+
+fn is_arithmetic(bin_op: BinOp) -> bool {
+    matches!(
+        bin_op,
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem | BinOp::Shl | BinOp::Shr
+    )
+}
+
+fn place_taint(taint: &[Option<usize>], place: &Place<'_>) -> Option<usize> {
+    taint[place.local.index()]
+}
+
+fn operand_taint(taint: &[Option<usize>], operand: &Operand<'_>) -> Option<usize> {
+    operand.place().and_then(|place| place_taint(taint, &place))
+}
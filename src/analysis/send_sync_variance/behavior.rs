@@ -42,12 +42,11 @@ pub(crate) enum AdtBehavior {
 bitflags! {
     // For each generic parameter of an ADT, ADT shows one or more of the behaviors below.
     pub struct AdtBehavior: u8 {
-        /* TODO: Implement fine-grained handling for ptr-like types.
         // At least one API of ADT takes `&self` within input and `&T` within output.
         const REF_REF = 0b00000001;
-        // `Clone` impl clones on generic param `T.
+        // `Clone` impl clones on generic param `T` (requires `T: Clone`),
+        // or the ADT doesn't implement `Clone` at all.
         const CLONED = 0b00000010;
-        */
 
         // `T` only appears in ADT API input/output as owned `T`.
         // (Sender/Receiver side of Queue APIs)
@@ -56,34 +55,27 @@ bitflags! {
 
         // Satisfies both of the following conditions:
         // * `&T` is not exposed in any method ret type.
-        // * TODO: There exists a method that takes closure `Fn(&T)` as input.
+        // * `&T` is not passed to any closure `Fn(&T)` taken as method input.
         //
         // Current limitation:
-        // This category may include cases where `&T` isn't directly exposed as ret type,
-        // but the ret type (ADT) has an API to expose `&T`.
-        // Greater precision can be achieved by implementing the following:
-        // * For more precision: check APIs of the ret types.
-        // * For even more precision: check (APIs of the ret types of (APIs of the ret types)).
-        // * Need more precision?: recurse until reaching your predefined maximum search depth.
-        // Inspection cost expected to be exponential to # of layers (of ADT).
+        // APIs of the ret types are only inspected up to `MAX_EXPOSURE_DEPTH` layers (of ADT),
+        // since the inspection cost is exponential to # of layers.
         const NO_DEREF = 0b00001000;
 
         // Satisfies either one of the following conditions:
         // * `&T` is exposed in method return type.
-        // * TODO: `&T` can be accessed by method input closure `Fn(&T)`
-        // Current limitation:
-        // May miss out on cases where `&T` isn't directly exposed as ret type,
-        // but the ret type (ADT) has an API to expose `&T`.
+        // * `&T` can be accessed by method input closure `Fn(&T)`
+        // * The ret type (ADT) borrows from `self` and has an API to expose `&T`.
+        //   e.g. `fn lock(&self) -> Guard<'_, T>` where `Guard<'_, T>: Deref<Target = T>`
         const DEREF = 0b00010000;
     }
 }
 
 impl AdtBehavior {
-    /* TODO: Implement fine-grained handling for ptr-like types.
-    fn ptr_like(&self) -> bool {
-        self.intersects(Cond::REF_REF & !Cond::CLONED)
+    /// Acts like a shared pointer (e.g. `Arc`), so `impl Send` needs `T: Send + Sync`.
+    pub fn is_ptr_like(&self) -> bool {
+        self.contains(AdtBehavior::REF_REF) && !self.contains(AdtBehavior::CLONED)
     }
-    */
     pub fn is_concurrent_queue(&self) -> bool {
         !self.intersects(AdtBehavior::DEREF) && self.intersects(AdtBehavior::PASS_OWNED)
     }
@@ -95,6 +87,10 @@ impl AdtBehavior {
     }
 }
 
+/// Maximum number of returned ADT layers to look through when searching for APIs that expose `&T`.
+/// e.g. `lock(&self) -> Guard<'_, T>` and then `<Guard<'_, T> as Deref>::deref(&self) -> &T`
+const MAX_EXPOSURE_DEPTH: usize = 2;
+
 // Enum to differentiate DefIds of
 // `&self` methods with constructor functions.
 enum FnType {
@@ -114,8 +110,12 @@ pub(crate) fn adt_behavior<'tcx>(
 
     // Set of `T`s that appear only as owned `T` in either input or output of APIs.
     let mut owned_generic_params = FxHashSet::default();
-    // Set of `T`s that appear only as `&T` in return type of APIs.
+    // Set of `T`s that appear as `&T` in return type of APIs or in closure arguments.
     let mut deref_generic_params = FxHashSet::default();
+    // Set of `T`s that are cloned when the ADT is cloned.
+    let mut cloned_generic_params = FxHashSet::default();
+    let mut has_clone_impl = false;
+    let clone_trait_did = tcx.lang_items().clone_trait();
 
     let adt_ty = tcx.type_of(adt_did);
     // For ADT `Foo<A, B>` => adt_ty_name = `Foo`
@@ -123,112 +123,157 @@ pub(crate) fn adt_behavior<'tcx>(
 
     let adt_generic_params = &tcx.generics_of(adt_did).params;
 
-    if let Some(relevant_impls) = rcx.index_adt_cache(&adt_did) {
-        // Inspect `impl`s relevant to the given ADT.
-        for (impl_hir_id, impl_self_ty) in relevant_impls.iter() {
-            if let ty::TyKind::Adt(impl_self_adt_def, impl_substs) = impl_self_ty.kind() {
-                let impl_self_ty_name = tcx.item_name(impl_self_adt_def.did);
-                if adt_ty_name != impl_self_ty_name {
-                    continue;
+    // Inspect `impl`s relevant to the given ADT, including the ones from other crates.
+    for impl_did in adt_impl_dids(rcx, adt_did) {
+        let impl_self_ty = tcx.type_of(impl_did);
+        if let ty::TyKind::Adt(impl_self_adt_def, impl_substs) = impl_self_ty.kind() {
+            let impl_self_ty_name = tcx.item_name(impl_self_adt_def.did);
+            if adt_ty_name != impl_self_ty_name {
+                continue;
+            }
+
+            // At this point, `adt_ty.name == impl_self_ty_name` . (Foo == Foo)
+
+            // There are three possiblities now..
+            // (1) adt_ty != impl_self_ty . (Foo<A, B> != Foo<i64, B>)
+            // (2) adt_ty != impl_self_ty . (Foo<A, B> != Foo<A, B: Send>)
+            // (3) adt_ty == impl_self_ty . (Foo<A, B> == Foo<A, B>)
+            // TODO: Should we cater to each of the possibilities?
+
+            // Since each `impl` block may assign different indices to equivalent generic parameters,
+            // We need one translation map per `impl` block.
+            let generic_param_idx_map = generic_param_idx_mapper(adt_generic_params, impl_substs);
+
+            // A `Clone` impl that requires `T: Clone` clones `T` (e.g. `#[derive(Clone)]`),
+            // while a shared pointer only copies the pointer (e.g. `impl<T> Clone for Arc<T>`).
+            let impl_trait_did = tcx
+                .impl_trait_ref(impl_did)
+                .map(|trait_ref| trait_ref.def_id);
+            if impl_trait_did.is_some() && impl_trait_did == clone_trait_did {
+                has_clone_impl = true;
+                for atom in tcx
+                    .param_env(impl_did)
+                    .caller_bounds()
+                    .iter()
+                    .map(|x| x.kind().skip_binder())
+                {
+                    if let PredicateKind::Trait(trait_predicate) = atom {
+                        if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                            if Some(trait_predicate.def_id()) == clone_trait_did {
+                                if let Some(&mapped_idx) =
+                                    generic_param_idx_map.get(&PreMapIdx(param_ty.index))
+                                {
+                                    cloned_generic_params.insert(mapped_idx);
+                                }
+                            }
+                        }
+                    }
                 }
+            }
 
-                // At this point, `adt_ty.name == impl_self_ty_name` . (Foo == Foo)
-
-                // There are three possiblities now..
-                // (1) adt_ty != impl_self_ty . (Foo<A, B> != Foo<i64, B>)
-                // (2) adt_ty != impl_self_ty . (Foo<A, B> != Foo<A, B: Send>)
-                // (3) adt_ty == impl_self_ty . (Foo<A, B> == Foo<A, B>)
-                // TODO: Should we cater to each of the possibilities?
-
-                // DefIds of `safe` functions (within the given impl block)
-                // which either satisfy the following.
-                // * Take `&self` within its first parameter type.
-                // * Construct the Self type, but don't contain `self` within its inputs.
-                let relevant_safe_fns = tcx
-                    .associated_items(impl_hir_id.to_def_id())
-                    .in_definition_order()
-                    .filter_map(|assoc_item| {
-                        if assoc_item.kind == AssocKind::Fn {
-                            let fn_did = assoc_item.def_id;
-                            let fn_sig = tcx.fn_sig(fn_did).skip_binder();
-                            if let rustc_hir::Unsafety::Unsafe = fn_sig.unsafety {
-                                return None;
+            // DefIds of `safe` functions (within the given impl block)
+            // which either satisfy the following.
+            // * Take `&self` within its first parameter type.
+            // * Construct the Self type, but don't contain `self` within its inputs.
+            let relevant_safe_fns = tcx
+                .associated_items(impl_did)
+                .in_definition_order()
+                .filter_map(|assoc_item| {
+                    if assoc_item.kind == AssocKind::Fn {
+                        let fn_did = assoc_item.def_id;
+                        let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+                        if let rustc_hir::Unsafety::Unsafe = fn_sig.unsafety {
+                            return None;
+                        }
+                        if assoc_item.fn_has_self_parameter {
+                            // Check if the given method takes `&self` within its first parameter's type.
+                            // We already know the method takes `self` within its first parameter,
+                            // so we only check whether the first parameter contains a reference.
+                            // e.g. `&self`, `Box<&self>`, `Pin<&self>`, ..
+                            if takes_borrowed_self(tcx, fn_sig.inputs()[0]) {
+                                return Some(FnType::TakeBorrowedSelf(fn_did));
                             }
-                            if assoc_item.fn_has_self_parameter {
-                                // Check if the given method takes `&self` within its first parameter's type.
-                                // We already know the method takes `self` within its first parameter,
-                                // so we only check whether the first parameter contains a reference.
-                                // e.g. `&self`, `Box<&self>`, `Pin<&self>`, ..
-                                let mut walker = fn_sig.inputs()[0].walk(tcx);
-                                while let Some(node) = walker.next() {
-                                    if let GenericArgKind::Type(ty) = node.unpack() {
-                                        if let ty::TyKind::Ref(_, _, Mutability::Not) = ty.kind() {
-                                            return Some(FnType::TakeBorrowedSelf(fn_did));
-                                        }
-                                    }
-                                }
-                            } else {
-                                // Check if the function return type equals `Self`.
-                                if TyS::same_type(fn_sig.output(), adt_ty) {
-                                    return Some(FnType::ConstructSelf(fn_did));
-                                }
+                        } else {
+                            // Check if the function return type equals `Self`.
+                            if TyS::same_type(fn_sig.output(), adt_ty) {
+                                return Some(FnType::ConstructSelf(fn_did));
                             }
                         }
-                        return None;
-                    });
-
-                // Since each `impl` block may assign different indices to equivalent generic parameters,
-                // We need one translation map per `impl` block.
-                let generic_param_idx_map =
-                    generic_param_idx_mapper(adt_generic_params, impl_substs);
-
-                // Inspect selected functions' input/output types to determine `AdtBehavior`.
-                for fn_type in relevant_safe_fns {
-                    match fn_type {
-                        FnType::ConstructSelf(fn_did) => {
-                            let fn_ctxt_pseudo_owned_param_idx_map =
-                                find_pseudo_owned_in_fn_ctxt(tcx, fn_did);
-                            let fn_sig = tcx.fn_sig(fn_did).skip_binder();
-                            // Check inputs of the constructor
-                            for input_ty in fn_sig.inputs() {
-                                for owned_idx in owned_generic_params_in_ty(tcx, input_ty)
-                                    .into_iter()
-                                    .map(|idx| {
-                                        *fn_ctxt_pseudo_owned_param_idx_map
-                                            .get(&idx)
-                                            .unwrap_or(&idx)
-                                    })
-                                {
-                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx)
-                                    {
-                                        owned_generic_params.insert(mapped_idx);
-                                    }
+                    }
+                    return None;
+                });
+
+            // Inspect selected functions' input/output types to determine `AdtBehavior`.
+            for fn_type in relevant_safe_fns {
+                match fn_type {
+                    FnType::ConstructSelf(fn_did) => {
+                        let fn_ctxt_pseudo_owned_param_idx_map =
+                            find_pseudo_owned_in_fn_ctxt(tcx, fn_did);
+                        let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+                        // Check inputs of the constructor
+                        for input_ty in fn_sig.inputs() {
+                            for owned_idx in owned_generic_params_in_ty(tcx, input_ty)
+                                .into_iter()
+                                .map(|idx| {
+                                    *fn_ctxt_pseudo_owned_param_idx_map.get(&idx).unwrap_or(&idx)
+                                })
+                            {
+                                if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx) {
+                                    owned_generic_params.insert(mapped_idx);
                                 }
                             }
                         }
-                        FnType::TakeBorrowedSelf(method_did) => {
-                            let fn_ctxt_pseudo_owned_param_idx_map =
-                                find_pseudo_owned_in_fn_ctxt(tcx, method_did);
-                            let fn_sig = tcx.fn_sig(method_did).skip_binder();
-
-                            // Check generic parameters that are passed as owned `T`.
-                            for ty in fn_sig.inputs_and_output.iter() {
-                                for owned_idx in
-                                    owned_generic_params_in_ty(tcx, ty).into_iter().map(|idx| {
-                                        *fn_ctxt_pseudo_owned_param_idx_map
-                                            .get(&idx)
-                                            .unwrap_or(&idx)
-                                    })
-                                {
-                                    if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx)
-                                    {
-                                        owned_generic_params.insert(mapped_idx);
-                                    }
+                    }
+                    FnType::TakeBorrowedSelf(method_did) => {
+                        let fn_ctxt_pseudo_owned_param_idx_map =
+                            find_pseudo_owned_in_fn_ctxt(tcx, method_did);
+                        let fn_sig = tcx.fn_sig(method_did).skip_binder();
+
+                        // Check generic parameters that are passed as owned `T`.
+                        for ty in fn_sig.inputs_and_output.iter() {
+                            for owned_idx in
+                                owned_generic_params_in_ty(tcx, ty).into_iter().map(|idx| {
+                                    *fn_ctxt_pseudo_owned_param_idx_map.get(&idx).unwrap_or(&idx)
+                                })
+                            {
+                                if let Some(&mapped_idx) = generic_param_idx_map.get(&owned_idx) {
+                                    owned_generic_params.insert(mapped_idx);
                                 }
                             }
+                        }
+
+                        // Check whether any of the methods return either `&T` or `Option<&T>` or `Result<&T>`.
+                        let output = normalized_output(tcx, method_did);
+                        for peek_idx in
+                            borrowed_generic_params_in_ty(tcx, output)
+                                .into_iter()
+                                .map(|idx| {
+                                    *fn_ctxt_pseudo_owned_param_idx_map.get(&idx).unwrap_or(&idx)
+                                })
+                        {
+                            if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx) {
+                                deref_generic_params.insert(mapped_idx);
+                            }
+                        }
+
+                        // Check whether any of the returned ADTs (e.g. guards) expose `&T`,
+                        // including the ones defined in other crates.
+                        for peek_idx in
+                            borrowed_generic_params_via_adts(rcx, output, MAX_EXPOSURE_DEPTH)
+                                .into_iter()
+                                .map(|idx| {
+                                    *fn_ctxt_pseudo_owned_param_idx_map.get(&idx).unwrap_or(&idx)
+                                })
+                        {
+                            if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx) {
+                                deref_generic_params.insert(mapped_idx);
+                            }
+                        }
 
-                            // Check whether any of the methods return either `&T` or `Option<&T>` or `Result<&T>`.
-                            for peek_idx in borrowed_generic_params_in_ty(tcx, fn_sig.output())
+                        // Check whether any of the method inputs are closures that take `&T`.
+                        // e.g. `fn with<F: FnOnce(&T)>(&self, f: F)`
+                        for closure_arg_ty in closure_arg_tys_in_fn(tcx, method_did) {
+                            for peek_idx in borrowed_generic_params_in_ty(tcx, closure_arg_ty)
                                 .into_iter()
                                 .map(|idx| {
                                     *fn_ctxt_pseudo_owned_param_idx_map.get(&idx).unwrap_or(&idx)
@@ -238,9 +283,6 @@ pub(crate) fn adt_behavior<'tcx>(
                                     deref_generic_params.insert(mapped_idx);
                                 }
                             }
-
-                            // TODO: Check whether any of the method inputs are closures of type `Fn(&T) -> !`.
-                            // for _closure_ty in fn_sig.inputs().iter().filter(|ty| ty.is_closure()) {}
                         }
                     }
                 }
@@ -270,7 +312,15 @@ pub(crate) fn adt_behavior<'tcx>(
         behavior_map
             .entry(param_idx)
             .or_insert(AdtBehavior::empty())
-            .insert(AdtBehavior::DEREF);
+            .insert(AdtBehavior::DEREF | AdtBehavior::REF_REF);
+    }
+    for &param_idx in all_generic_params.iter() {
+        if !has_clone_impl || cloned_generic_params.contains(&param_idx) {
+            behavior_map
+                .entry(param_idx)
+                .or_insert_with(AdtBehavior::empty)
+                .insert(AdtBehavior::CLONED);
+        }
     }
     for &param_idx in owned_generic_params.difference(&deref_generic_params) {
         behavior_map
@@ -281,3 +331,156 @@ pub(crate) fn adt_behavior<'tcx>(
 
     return behavior_map;
 }
+
+// Check whether the first parameter of a method contains `&self`.
+fn takes_borrowed_self<'tcx>(tcx: TyCtxt<'tcx>, self_ty: Ty<'tcx>) -> bool {
+    self_ty.walk(tcx).any(|node| {
+        matches!(
+            node.unpack(),
+            GenericArgKind::Type(ty) if matches!(ty.kind(), ty::TyKind::Ref(_, _, Mutability::Not))
+        )
+    })
+}
+
+/// DefIds of `impl`s of the given ADT.
+/// Local `impl`s come from `AdtImplMap`. For ADTs defined in other crates,
+/// inherent `impl`s and `impl`s of `Deref` and `DerefMut` come from crate metadata.
+fn adt_impl_dids(rcx: RudraCtxt<'_>, adt_did: DefId) -> Vec<DefId> {
+    let mut impl_dids: Vec<DefId> = rcx
+        .index_adt_cache(&adt_did)
+        .map(|impls| {
+            impls
+                .iter()
+                .map(|(impl_id, _)| impl_id.to_def_id())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if !adt_did.is_local() {
+        impl_dids.extend(rcx.external_adt_impls(adt_did).iter().copied());
+    }
+    impl_dids
+}
+
+// Return type of the given function, with associated types normalized.
+// e.g. `Deref::deref()` usually returns `&Self::Target`.
+fn normalized_output(tcx: TyCtxt<'_>, fn_did: DefId) -> Ty<'_> {
+    let fn_sig = tcx.fn_sig(fn_did);
+    if fn_sig.skip_binder().output().has_projections() {
+        tcx.normalize_erasing_late_bound_regions(tcx.param_env(fn_did), fn_sig)
+            .output()
+    } else {
+        fn_sig.skip_binder().output()
+    }
+}
+
+// Within the given `ty`, find ADTs that borrow data (e.g. `Guard<'_, T>`, `Ref<'_, T>`)
+// or that are borrowed (e.g. `&RefCell<T>` from another crate),
+// and return generic parameters that their `&self` APIs expose as `&T`.
+// Owned ADTs like `Option<T>` are skipped, since they hand over `T` itself.
+// Guards with exclusive access (e.g. `MutexGuard<'_, T>`) are skipped as well,
+// since `&T` from them is never shared across threads.
+fn borrowed_generic_params_via_adts<'tcx>(
+    rcx: RudraCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    depth: usize,
+) -> FxHashSet<PreMapIdx> {
+    let tcx = rcx.tcx();
+    let mut borrowed_generic_params = FxHashSet::default();
+    if depth == 0 {
+        return borrowed_generic_params;
+    }
+
+    for node in ty.walk(tcx) {
+        if let GenericArgKind::Type(ty) = node.unpack() {
+            let (adt_def, substs) = match ty.kind() {
+                ty::TyKind::Adt(adt_def, substs) if substs.regions().next().is_some() => {
+                    (adt_def, substs)
+                }
+                ty::TyKind::Ref(_, borrowed_ty, Mutability::Not) => match borrowed_ty.kind() {
+                    ty::TyKind::Adt(adt_def, substs) => (adt_def, substs),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            if gives_exclusive_access(tcx, adt_def.did) {
+                continue;
+            }
+            for PostMapIdx(exposed_idx) in exposed_generic_params(rcx, adt_def.did, depth - 1) {
+                if let Some(GenericArgKind::Type(arg_ty)) =
+                    substs.get(exposed_idx as usize).map(|arg| arg.unpack())
+                {
+                    if let ty::TyKind::Param(param_ty) = arg_ty.kind() {
+                        borrowed_generic_params.insert(PreMapIdx(param_ty.index));
+                    }
+                }
+            }
+        }
+    }
+    borrowed_generic_params
+}
+
+// Check whether the given guard-like type implements `DerefMut`.
+// Such guards are only handed out to one owner at a time (e.g. `Mutex::lock()`).
+fn gives_exclusive_access(tcx: TyCtxt<'_>, adt_did: DefId) -> bool {
+    let deref_mut_trait = match tcx.lang_items().deref_mut_trait() {
+        Some(deref_mut_trait) => deref_mut_trait,
+        None => return false,
+    };
+    let mut found = false;
+    // Blanket impls (e.g. `impl DerefMut for &mut T`) are relevant to every type
+    tcx.for_each_relevant_impl(deref_mut_trait, tcx.type_of(adt_did), |impl_did| {
+        found |= tcx
+            .type_of(impl_did)
+            .ty_adt_def()
+            .map(|adt_def| adt_def.did)
+            == Some(adt_did);
+    });
+    found
+}
+
+/// Generic parameters of the given ADT that are exposed as `&T` by one of its `safe` `&self` methods,
+/// looking through the returned ADTs for up to `depth` more layers.
+fn exposed_generic_params(
+    rcx: RudraCtxt<'_>,
+    adt_did: DefId,
+    depth: usize,
+) -> FxHashSet<PostMapIdx> {
+    let tcx = rcx.tcx();
+    let adt_generic_params = &tcx.generics_of(adt_did).params;
+    let mut exposed_generic_params = FxHashSet::default();
+
+    for impl_did in adt_impl_dids(rcx, adt_did) {
+        let impl_substs = match tcx.type_of(impl_did).kind() {
+            ty::TyKind::Adt(_, impl_substs) => impl_substs,
+            _ => continue,
+        };
+        let generic_param_idx_map = generic_param_idx_mapper(adt_generic_params, impl_substs);
+
+        for assoc_item in tcx.associated_items(impl_did).in_definition_order() {
+            if assoc_item.kind != AssocKind::Fn || !assoc_item.fn_has_self_parameter {
+                continue;
+            }
+            let fn_did = assoc_item.def_id;
+            let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+            if let rustc_hir::Unsafety::Unsafe = fn_sig.unsafety {
+                continue;
+            }
+            if !takes_borrowed_self(tcx, fn_sig.inputs()[0]) {
+                continue;
+            }
+
+            let output = normalized_output(tcx, fn_did);
+
+            for peek_idx in borrowed_generic_params_in_ty(tcx, output)
+                .into_iter()
+                .chain(borrowed_generic_params_via_adts(rcx, output, depth))
+            {
+                if let Some(&mapped_idx) = generic_param_idx_map.get(&peek_idx) {
+                    exposed_generic_params.insert(mapped_idx);
+                }
+            }
+        }
+    }
+    exposed_generic_params
+}
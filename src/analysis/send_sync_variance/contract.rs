@@ -0,0 +1,198 @@
+//! Unsafe trait contract checker
+//!
+//! Checks impls of unsafe traits declared in the user config (`[[unsafe_trait]]`),
+//! whose safety contracts require each generic parameter or each field of the ADT to satisfy a bound.
+//! e.g. `bytemuck::Pod` requires every field to be `Pod`.
+
+use super::*;
+use crate::config::{user_config, UnsafeTraitConfig};
+use crate::paths::parse_path;
+use crate::progress_warn;
+
+/// Resolved safety contract of an unsafe trait
+struct TraitContract {
+    trait_did: DefId,
+    param_bound: Vec<DefId>,
+    field_bound: Vec<DefId>,
+}
+
+/// (DefId of ADT, descriptions of violations, bounds that generic parameters need)
+type ContractViolation = (DefId, Vec<String>, Vec<(PostMapIdx, Vec<DefId>)>);
+
+impl<'tcx> SendSyncVarianceChecker<'tcx> {
+    /// Detect impls that violate the contracts of unsafe traits declared in the user config.
+    pub(super) fn analyze_trait_contracts(&mut self) {
+        let tcx = self.rcx.tcx();
+        for contract in user_config()
+            .unsafe_trait
+            .iter()
+            .filter_map(|config| resolve_contract(tcx, config))
+        {
+            for impl_did in tcx.all_impls(contract.trait_did) {
+                let impl_id = match impl_did.as_local() {
+                    Some(impl_id) => impl_id,
+                    None => continue,
+                };
+                if let Some((adt_did, violations, required_bounds)) =
+                    self.violated_contract(impl_id, &contract)
+                {
+                    let analyses = BehaviorFlag::TRAIT_CONTRACT;
+                    if analyses.report_level() < self.rcx.report_level()
+                        || self.rcx.is_suppressed("send_sync_variance", impl_did)
+                    {
+                        continue;
+                    }
+
+                    let mut report = Report::with_hir_id(
+                        tcx,
+                        analyses.report_level(),
+                        AnalysisKind::SendSyncVariance(analyses),
+                        format!(
+                            "Impl of `{}` violates its contract: {}",
+                            tcx.def_path_str(contract.trait_did),
+                            violations.join(", ")
+                        ),
+                        impl_id,
+                    );
+                    if let Some(suggestion) = self.suggest_bounds(impl_id, &required_bounds) {
+                        report = report.with_suggestion(suggestion);
+                    }
+                    self.report_map
+                        .entry(adt_did)
+                        .or_insert_with(|| Vec::with_capacity(2))
+                        .push(report);
+                }
+            }
+        }
+    }
+
+    /// Returns `Some(_)` if the impl violates the contract.
+    fn violated_contract(
+        &mut self,
+        impl_id: LocalDefId,
+        contract: &TraitContract,
+    ) -> Option<ContractViolation> {
+        let tcx = self.rcx.tcx();
+        if let ty::ImplPolarity::Negative = tcx.impl_polarity(impl_id) {
+            return None;
+        }
+        let trait_ref = tcx.impl_trait_ref(impl_id)?;
+        let (adt_def, impl_substs) = match trait_ref.self_ty().kind() {
+            ty::TyKind::Adt(adt_def, impl_substs) => (adt_def, impl_substs),
+            _ => return None,
+        };
+        let adt_did = adt_def.did;
+        let param_env = tcx.param_env(impl_id);
+
+        let mut violations = Vec::new();
+        let mut required_bounds = Vec::new();
+
+        // Generic params that only occur within `PhantomData<_>`
+        let phantom_params = self
+            .phantom_map
+            .entry(adt_did)
+            .or_insert_with(|| phantom_indices(tcx, tcx.type_of(adt_did)));
+        for gen_param in tcx.generics_of(adt_did).params.iter() {
+            if !matches!(gen_param.kind, GenericParamDefKind::Type { .. })
+                || phantom_params.contains(&gen_param.index)
+            {
+                continue;
+            }
+            let param_ty = impl_substs.type_at(gen_param.index as usize);
+
+            let missing = contract
+                .param_bound
+                .iter()
+                .copied()
+                .filter(|&bound_did| !implements_trait(tcx, param_ty, bound_did, param_env))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                violations.push(format!(
+                    "`{}` is not bounded by {}",
+                    param_ty,
+                    trait_names(tcx, &missing)
+                ));
+                required_bounds.push((PostMapIdx(gen_param.index), missing));
+            }
+        }
+
+        for field in adt_def.all_fields() {
+            // `PhantomData<_>` fields are zero-sized markers
+            let field_ty = field.ty(tcx, impl_substs);
+            if field_ty.is_phantom_data() {
+                continue;
+            }
+            let missing = contract
+                .field_bound
+                .iter()
+                .copied()
+                .filter(|&bound_did| !implements_trait(tcx, field_ty, bound_did, param_env))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                violations.push(format!(
+                    "field `{}: {}` does not implement {}",
+                    field.ident,
+                    field_ty,
+                    trait_names(tcx, &missing)
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            None
+        } else {
+            Some((adt_did, violations, required_bounds))
+        }
+    }
+}
+
+/// Finds the traits of the contract. Returns `None` if the unsafe trait is not used in this crate.
+/// Paths that can't be checked are logged, since a typo in the config would silently disable the check.
+fn resolve_contract(tcx: TyCtxt<'_>, config: &UnsafeTraitConfig) -> Option<TraitContract> {
+    let ext = tcx.ext();
+    let resolve = |path: &str| {
+        let parsed = parse_path(path);
+        let trait_did = tcx
+            .all_traits(())
+            .iter()
+            .copied()
+            .find(|&trait_did| ext.get_def_path(trait_did) == parsed);
+        if trait_did.is_none() {
+            progress_warn!("Unsafe trait config: trait `{}` is not found", path);
+        }
+        trait_did
+    };
+    // Bounds that don't exist in this crate can't be satisfied, but can't be checked either.
+    let resolve_all = |paths: &[String]| {
+        paths
+            .iter()
+            .filter_map(|path| resolve(path).map(|trait_did| (path, trait_did)))
+            .filter(|&(path, trait_did)| {
+                // Only bounds of the form `T: Bound` (no other generic parameters) can be checked
+                let checkable = tcx.generics_of(trait_did).count() == 1;
+                if !checkable {
+                    progress_warn!(
+                        "Unsafe trait config: bound `{}` has generic parameters and is skipped",
+                        path
+                    );
+                }
+                checkable
+            })
+            .map(|(_, trait_did)| trait_did)
+            .collect()
+    };
+
+    Some(TraitContract {
+        trait_did: resolve(&config.path)?,
+        param_bound: resolve_all(&config.param_bound),
+        field_bound: resolve_all(&config.field_bound),
+    })
+}
+
+fn trait_names(tcx: TyCtxt<'_>, trait_dids: &[DefId]) -> String {
+    trait_dids
+        .iter()
+        .map(|&trait_did| format!("`{}`", tcx.def_path_str(trait_did)))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
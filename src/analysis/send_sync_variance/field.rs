@@ -0,0 +1,171 @@
+//! Identify field types that are known to be `!Send`/`!Sync`,
+//! regardless of the generic parameters of the ADT.
+
+use super::*;
+
+// Types whose thread safety is decided by the ADT that owns them.
+// Overriding them with `unsafe impl Send/Sync` is the expected usage (raw pointers are skipped as well).
+const OWNER_DEFINED: &[&[&str]] = &[
+    &["core", "ptr", "non_null", "NonNull"],
+    &["core", "cell", "UnsafeCell"],
+];
+
+impl<'tcx> SendSyncVarianceChecker<'tcx> {
+    /// Adds `NON_SEND_SYNC_FIELD` to the result of the other analyses
+    /// if a field type of the ADT is known not to implement the trait of the impl.
+    /// Returns the field type as well.
+    pub fn check_fields(
+        &self,
+        impl_id: LocalDefId,
+        send_trait_did: DefId,
+        sync_trait_did: DefId,
+        detected: Option<SuspiciousImpl>,
+    ) -> (Option<SuspiciousImpl>, Option<Ty<'tcx>>) {
+        let tcx = self.rcx.tcx();
+        let trait_ref = match tcx.impl_trait_ref(impl_id) {
+            Some(trait_ref) => trait_ref,
+            None => return (detected, None),
+        };
+        let adt_def = match trait_ref.self_ty().kind() {
+            ty::TyKind::Adt(adt_def, _) => adt_def,
+            _ => return (detected, None),
+        };
+
+        let field_ty = match non_thread_safe_field(
+            tcx,
+            trait_ref.self_ty(),
+            trait_ref.def_id,
+            send_trait_did,
+            sync_trait_did,
+        ) {
+            Some(field_ty) => field_ty,
+            None => return (detected, None),
+        };
+        let detected = match detected {
+            Some((adt_did, analyses, param_analyses)) => (
+                adt_did,
+                analyses | BehaviorFlag::NON_SEND_SYNC_FIELD,
+                param_analyses,
+            ),
+            None => (
+                adt_def.did,
+                BehaviorFlag::NON_SEND_SYNC_FIELD,
+                FxHashMap::default(),
+            ),
+        };
+        (Some(detected), Some(field_ty))
+    }
+}
+
+/// Within the fields of the given ADT type, find a type that is known not to implement `trait_did` (`Send`/`Sync`).
+/// e.g. `Rc<_>`, `Cell<_>` (for `Sync`), `MutexGuard<'_, _>` (for `Send`)
+///
+/// Fields of other ADTs are inspected transitively.
+/// If an ADT decides the trait with an explicit impl (e.g. `impl<T: Send + Sync> Send for Arc<T>`),
+/// the bounds of the impl are inspected instead of its fields.
+pub fn non_thread_safe_field<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_ty: Ty<'tcx>,
+    trait_did: DefId,
+    send_trait_did: DefId,
+    sync_trait_did: DefId,
+) -> Option<Ty<'tcx>> {
+    let ext = tcx.ext();
+    let (adt_def, substs) = match adt_ty.kind() {
+        ty::TyKind::Adt(adt_def, substs) => (adt_def, substs),
+        _ => return None,
+    };
+
+    let mut worklist = adt_def
+        .all_fields()
+        .map(|field| (field.ty(tcx, substs), trait_did))
+        .collect::<Vec<_>>();
+    let mut visited = FxHashSet::default();
+    while let Some((ty, trait_did)) = worklist.pop() {
+        if !visited.insert((ty, trait_did)) {
+            continue;
+        }
+
+        match ty.kind() {
+            // `&T: Send` and `&T: Sync` both require `T: Sync`
+            ty::TyKind::Ref(_, ty, Mutability::Not) => worklist.push((ty, sync_trait_did)),
+            ty::TyKind::Ref(_, ty, Mutability::Mut)
+            | ty::TyKind::Array(ty, _)
+            | ty::TyKind::Slice(ty) => worklist.push((ty, trait_did)),
+            ty::TyKind::Tuple(substs) => {
+                worklist.extend(substs.types().map(|ty| (ty, trait_did)));
+            }
+            ty::TyKind::Adt(adt_def, substs) => {
+                if OWNER_DEFINED
+                    .iter()
+                    .any(|path| ext.match_def_path(adt_def.did, path))
+                {
+                    continue;
+                }
+                if ty.is_phantom_data() {
+                    worklist.extend(substs.types().map(|ty| (ty, trait_did)));
+                    continue;
+                }
+
+                let explicit_impls = tcx
+                    .all_impls(trait_did)
+                    .filter(|&impl_did| {
+                        tcx.type_of(impl_did)
+                            .ty_adt_def()
+                            .map(|adt_def| adt_def.did)
+                            == Some(adt_def.did)
+                    })
+                    .collect::<Vec<_>>();
+                if explicit_impls.is_empty() {
+                    // Auto trait impl
+                    worklist.extend(
+                        adt_def
+                            .all_fields()
+                            .map(|field| (field.ty(tcx, substs), trait_did)),
+                    );
+                    continue;
+                }
+
+                for impl_did in explicit_impls {
+                    if let ty::ImplPolarity::Negative = tcx.impl_polarity(impl_did) {
+                        return Some(ty);
+                    }
+
+                    let impl_substs = match tcx.type_of(impl_did).kind() {
+                        ty::TyKind::Adt(_, impl_substs) => impl_substs,
+                        _ => continue,
+                    };
+                    let generic_param_idx_map =
+                        generic_param_idx_mapper(&tcx.generics_of(adt_def.did).params, impl_substs);
+                    for atom in tcx
+                        .param_env(impl_did)
+                        .caller_bounds()
+                        .iter()
+                        .map(|x| x.kind().skip_binder())
+                    {
+                        if let PredicateKind::Trait(trait_predicate) = atom {
+                            let bound_trait_did = trait_predicate.def_id();
+                            if bound_trait_did != send_trait_did
+                                && bound_trait_did != sync_trait_did
+                            {
+                                continue;
+                            }
+                            if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                                if let Some(GenericArgKind::Type(arg_ty)) = generic_param_idx_map
+                                    .get(&PreMapIdx(param_ty.index))
+                                    .and_then(|post_map_idx| substs.get(post_map_idx.0 as usize))
+                                    .map(|arg| arg.unpack())
+                                {
+                                    worklist.push((arg_ty, bound_trait_did));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            // Raw pointers are owner-defined, and generic parameters are handled by the other analyses.
+            _ => {}
+        }
+    }
+    None
+}
@@ -1,24 +1,27 @@
 //! Unsafe Send/Sync impl detector
 
 mod behavior;
+mod contract;
+mod field;
 mod phantom;
-// You need to fix the code to enable `relaxed` mode..
 mod relaxed;
 // Default mode is `strict`.
 mod strict;
+mod suggest;
 mod utils;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_hir::{
-    GenericBound, GenericParam, GenericParamKind, HirId, Impl, ImplPolarity, ItemId, ItemKind,
-    Node, WherePredicate,
+    GenericBound, GenericParam, GenericParamKind, Impl, ImplPolarity, ItemId, ItemKind, Node,
+    WherePredicate,
 };
 use rustc_middle::mir::terminator::Mutability;
 use rustc_middle::ty::{
     self,
     subst::{self, GenericArgKind},
     AssocKind, GenericParamDef, GenericParamDefKind, List, PredicateKind, Ty, TyCtxt, TyS,
+    TypeFoldable,
 };
 use rustc_span::symbol::sym;
 
@@ -29,13 +32,28 @@ use crate::prelude::*;
 use crate::report::{Report, ReportLevel};
 
 use behavior::*;
+pub use field::*;
 pub use phantom::*;
 pub use relaxed::*;
 pub use strict::*;
 pub use utils::*;
 
+/// Determines which generic parameters of a `Send`/`Sync` impl must be bounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendSyncMode {
+    /// Every generic parameter needs a bound, depending on the API of the ADT.
+    Strict,
+    /// Reports only if no generic parameter is bounded by `Send`/`Sync`,
+    /// either directly, in a where clause, on an associated type, or through a super-trait.
+    Relaxed,
+}
+
+/// A suspicious `Send`/`Sync` impl: (DefId of ADT, detected analyses, analyses per generic param)
+pub type SuspiciousImpl = (DefId, BehaviorFlag, FxHashMap<PostMapIdx, BehaviorFlag>);
+
 pub struct SendSyncVarianceChecker<'tcx> {
     rcx: RudraCtxt<'tcx>,
+    mode: SendSyncMode,
     /// For each ADT, keep track of reports.
     report_map: FxHashMap<DefId, Vec<Report>>,
     /// For each ADT, keep track of `T`s that are only within `PhantomData<T>`.
@@ -45,9 +63,10 @@ pub struct SendSyncVarianceChecker<'tcx> {
 }
 
 impl<'tcx> SendSyncVarianceChecker<'tcx> {
-    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+    pub fn new(rcx: RudraCtxt<'tcx>, mode: SendSyncMode) -> Self {
         SendSyncVarianceChecker {
             rcx,
+            mode,
             report_map: FxHashMap::default(),
             phantom_map: FxHashMap::default(),
             behavior_map: FxHashMap::default(),
@@ -62,6 +81,7 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
         // Main analysis
         self.analyze_send(send_trait_did, sync_trait_did, copy_trait_did);
         self.analyze_sync(send_trait_did, sync_trait_did, copy_trait_did);
+        self.analyze_trait_contracts();
 
         // Report any suspicious `Send`/`Sync` impls on the given struct.
         for (_struct_def_id, reports) in self.report_map.into_iter() {
@@ -85,21 +105,48 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
             if_chain! {
                 if let ItemKind::Impl(impl_item) = &item.kind;
                 if impl_item.polarity == ImplPolarity::Positive;
-                if let Some((adt_def_id, send_sync_analyses)) =
-                    self.suspicious_send(impl_id, send_trait_did, sync_trait_did, copy_trait_did);
+                let detected = match self.mode {
+                    SendSyncMode::Strict => {
+                        self.suspicious_send(impl_id, send_trait_did, sync_trait_did, copy_trait_did)
+                    }
+                    SendSyncMode::Relaxed => {
+                        self.suspicious_send_relaxed(impl_id, send_trait_did, sync_trait_did)
+                    }
+                };
+                // Field types that are `!Send` regardless of generic parameters
+                let (detected, non_send_field) =
+                    self.check_fields(impl_id, send_trait_did, sync_trait_did, detected);
+                if let Some((adt_def_id, send_sync_analyses, param_analyses)) = detected;
                 if send_sync_analyses.report_level() >= self.rcx.report_level();
+                if !self.rcx.is_suppressed("send_sync_variance", impl_id.to_def_id());
                 then {
                     let tcx = self.rcx.tcx();
+                    let mut report = Report::with_hir_id(
+                        tcx,
+                        send_sync_analyses.report_level(),
+                        AnalysisKind::SendSyncVariance(send_sync_analyses),
+                        match non_send_field {
+                            Some(field_ty) => format!(
+                                "Suspicious impl of `Send` found: field type `{}` is not `Send`",
+                                field_ty
+                            ),
+                            None => "Suspicious impl of `Send` found".to_owned(),
+                        },
+                        impl_id,
+                    );
+                    let required_bounds = self.required_send_sync_bounds(
+                        impl_id,
+                        &param_analyses,
+                        send_trait_did,
+                        sync_trait_did,
+                    );
+                    if let Some(suggestion) = self.suggest_bounds(impl_id, &required_bounds) {
+                        report = report.with_suggestion(suggestion);
+                    }
                     self.report_map
                         .entry(adt_def_id)
                         .or_insert_with(|| Vec::with_capacity(2))
-                        .push(Report::with_hir_id(
-                            tcx,
-                            send_sync_analyses.report_level(),
-                            AnalysisKind::SendSyncVariance(send_sync_analyses),
-                            "Suspicious impl of `Send` found",
-                            impl_id,
-                        ));
+                        .push(report);
                 }
             }
         }
@@ -120,21 +167,46 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
             if_chain! {
                 if let ItemKind::Impl(impl_item) = &item.kind;
                 if impl_item.polarity == ImplPolarity::Positive;
-                if let Some((struct_def_id, send_sync_analyses)) =
-                    self.suspicious_sync(impl_id, send_trait_did, sync_trait_did, copy_trait_did);
+                let detected = match self.mode {
+                    SendSyncMode::Strict => {
+                        self.suspicious_sync(impl_id, send_trait_did, sync_trait_did, copy_trait_did)
+                    }
+                    SendSyncMode::Relaxed => self.suspicious_sync_relaxed(impl_id, sync_trait_did),
+                };
+                // Field types that are `!Sync` regardless of generic parameters
+                let (detected, non_sync_field) =
+                    self.check_fields(impl_id, send_trait_did, sync_trait_did, detected);
+                if let Some((struct_def_id, send_sync_analyses, param_analyses)) = detected;
                 if send_sync_analyses.report_level() >= self.rcx.report_level();
+                if !self.rcx.is_suppressed("send_sync_variance", impl_id.to_def_id());
                 then {
                     let tcx = self.rcx.tcx();
+                    let mut report = Report::with_hir_id(
+                        tcx,
+                        send_sync_analyses.report_level(),
+                        AnalysisKind::SendSyncVariance(send_sync_analyses),
+                        match non_sync_field {
+                            Some(field_ty) => format!(
+                                "Suspicious impl of `Sync` found: field type `{}` is not `Sync`",
+                                field_ty
+                            ),
+                            None => "Suspicious impl of `Sync` found".to_owned(),
+                        },
+                        impl_id,
+                    );
+                    let required_bounds = self.required_send_sync_bounds(
+                        impl_id,
+                        &param_analyses,
+                        send_trait_did,
+                        sync_trait_did,
+                    );
+                    if let Some(suggestion) = self.suggest_bounds(impl_id, &required_bounds) {
+                        report = report.with_suggestion(suggestion);
+                    }
                     self.report_map
                         .entry(struct_def_id)
                         .or_insert_with(|| Vec::with_capacity(2))
-                        .push(Report::with_hir_id(
-                            tcx,
-                            send_sync_analyses.report_level(),
-                            AnalysisKind::SendSyncVariance(send_sync_analyses),
-                            "Suspicious impl of `Sync` found",
-                            impl_id,
-                        ));
+                        .push(report);
                 }
             }
         }
@@ -197,7 +269,7 @@ pub struct PostMapIdx(u32);
 
 bitflags! {
     #[derive(Default)]
-    pub struct BehaviorFlag: u8 {
+    pub struct BehaviorFlag: u16 {
         // T: Send for impl Sync (with api check & phantom check)
         const API_SEND_FOR_SYNC = 0b00000001;
         // T: Sync for impl Sync (with api check & phantom check)
@@ -212,12 +284,22 @@ bitflags! {
         const RELAX_SEND = 0b00100000;
         // Relaxed Sync for impl Sync (with phantom check)
         const RELAX_SYNC = 0b01000000;
+        // T: Send + Sync for impl Send on pointer-like ADTs (with api check & phantom check)
+        const PTR_LIKE_SEND_FOR_SEND = 0b10000000;
+        // A field type is known to be `!Send` for impl Send, or `!Sync` for impl Sync (no api check)
+        const NON_SEND_SYNC_FIELD = 0b100000000;
+        // Violates the contract of an unsafe trait declared in the user config
+        const TRAIT_CONTRACT = 0b1000000000;
     }
 }
 
 impl IntoReportLevel for BehaviorFlag {
     fn report_level(&self) -> ReportLevel {
-        let high = BehaviorFlag::API_SEND_FOR_SYNC | BehaviorFlag::RELAX_SEND;
+        let high = BehaviorFlag::API_SEND_FOR_SYNC
+            | BehaviorFlag::RELAX_SEND
+            | BehaviorFlag::PTR_LIKE_SEND_FOR_SEND
+            | BehaviorFlag::NON_SEND_SYNC_FIELD
+            | BehaviorFlag::TRAIT_CONTRACT;
         let med = BehaviorFlag::API_SYNC_FOR_SYNC
             | BehaviorFlag::PHANTOM_SEND_FOR_SEND
             | BehaviorFlag::RELAX_SYNC;
@@ -1,22 +1,20 @@
 //! Unsafe Send/Sync impl detector (relaxed)
-#![allow(dead_code)]
 
 use super::*;
 
-// We may not use the relaxed versions at all,
-// but keeping them alive just in case..
 impl<'tcx> SendSyncVarianceChecker<'tcx> {
     /// Detect suspicious `Send` with relaxed rules.
     /// Report only if all generic parameters of `impl Send` aren't `Send`.
-    fn suspicious_send_relaxed(
+    pub fn suspicious_send_relaxed(
         &self,
-        hir_id: HirId,
+        impl_id: LocalDefId,
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
-    ) -> bool {
-        let map = self.rcx.tcx().hir();
+    ) -> Option<SuspiciousImpl> {
+        let tcx = self.rcx.tcx();
+        let map = tcx.hir();
         if_chain! {
-            if let Some(node) = map.find(hir_id);
+            if let Some(node) = map.find(map.local_def_id_to_hir_id(impl_id));
             if let Node::Item(item) = node;
             if let ItemKind::Impl(Impl {
                 ref generics,
@@ -24,10 +22,11 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                 ..
             }) = item.kind;
             if Some(send_trait_def_id) == trait_ref.trait_def_id();
+            if let Some(adt_did) = impl_adt_def_id(tcx, impl_id);
             then {
                 // If `impl Send` doesn't involve generic parameters, don't catch it.
-                if generics.params.len() == 0 {
-                    return false;
+                if !has_type_params(generics.params) {
+                    return None;
                 }
 
                 // Inspect immediate trait bounds on generic parameters
@@ -35,30 +34,38 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     &[send_trait_def_id, sync_trait_def_id],
                     generics.params
                 ) {
-                    return false;
+                    return None;
                 }
 
                 // Inspect trait bounds in where clauses
-                return !self.trait_in_where_relaxed(
+                if self.trait_in_where_relaxed(
                     &[send_trait_def_id, sync_trait_def_id],
                     generics.where_clause.predicates
-                );
+                ) {
+                    return None;
+                }
+
+                return Some((
+                    adt_did,
+                    BehaviorFlag::RELAX_SEND,
+                    relaxed_param_analyses(tcx, adt_did, BehaviorFlag::RELAX_SEND),
+                ));
             }
         }
-        return false;
+        None
     }
 
     /// Detect suspicious Sync with relaxed rules.
     /// Report only if all generic parameters of `impl Sync` aren't Sync.
-    fn suspicious_sync_relaxed(
+    pub fn suspicious_sync_relaxed(
         &self,
-        // HirId of the `Impl Sync` item
-        hir_id: HirId,
+        impl_id: LocalDefId,
         sync_trait_def_id: DefId,
-    ) -> bool {
-        let map = self.rcx.tcx().hir();
+    ) -> Option<SuspiciousImpl> {
+        let tcx = self.rcx.tcx();
+        let map = tcx.hir();
         if_chain! {
-            if let Some(node) = map.find(hir_id);
+            if let Some(node) = map.find(map.local_def_id_to_hir_id(impl_id));
             if let Node::Item(item) = node;
             if let ItemKind::Impl(Impl {
                 ref generics,
@@ -66,10 +73,11 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                 ..
             }) = item.kind;
             if Some(sync_trait_def_id) == trait_ref.trait_def_id();
+            if let Some(adt_did) = impl_adt_def_id(tcx, impl_id);
             then {
                 // If `impl Sync` doesn't involve generic parameters, don't catch it.
-                if generics.params.len() == 0 {
-                    return false;
+                if !has_type_params(generics.params) {
+                    return None;
                 }
 
                 // Inspect immediate trait bounds on generic parameters
@@ -77,16 +85,25 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                    &[sync_trait_def_id],
                    generics.params
                 ) {
-                   return false;
+                   return None;
                 }
 
-                return !self.trait_in_where_relaxed(
+                // Inspect trait bounds in where clauses
+                if self.trait_in_where_relaxed(
                     &[sync_trait_def_id],
                     generics.where_clause.predicates
-                );
+                ) {
+                    return None;
+                }
+
+                return Some((
+                    adt_did,
+                    BehaviorFlag::RELAX_SYNC,
+                    relaxed_param_analyses(tcx, adt_did, BehaviorFlag::RELAX_SYNC),
+                ));
             }
         }
-        return false;
+        None
     }
 
     fn trait_in_imm_relaxed(
@@ -94,57 +111,89 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
         target_trait_def_ids: &[DefId],
         generic_params: &[GenericParam],
     ) -> bool {
-        for generic_param in generic_params {
-            if let GenericParamKind::Type { .. } = generic_param.kind {
-                for bound in generic_param.bounds {
-                    if let GenericBound::Trait(x, ..) = bound {
-                        if let Some(def_id) = x.trait_ref.trait_def_id() {
-                            if target_trait_def_ids.contains(&def_id) {
-                                return true;
-                            }
-
-                            // Check super-traits
-                            for p in self.rcx.tcx().super_predicates_of(def_id).predicates {
-                                if let PredicateKind::Trait(x) = p.0.kind().skip_binder() {
-                                    if target_trait_def_ids.contains(&x.trait_ref.def_id) {
-                                        return true;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        return false;
+        generic_params.iter().any(|generic_param| {
+            matches!(generic_param.kind, GenericParamKind::Type { .. })
+                && self.trait_in_bounds_relaxed(target_trait_def_ids, generic_param.bounds)
+        })
     }
 
+    /// Bounds on associated types (`where P::Item: Send`) are accepted as well.
     fn trait_in_where_relaxed(
         &self,
         target_trait_def_ids: &[DefId],
         where_predicates: &[WherePredicate],
     ) -> bool {
-        for where_predicate in where_predicates {
-            if let WherePredicate::BoundPredicate(x) = where_predicate {
-                for bound in x.bounds {
-                    if let GenericBound::Trait(y, ..) = bound {
-                        if let Some(def_id) = y.trait_ref.trait_def_id() {
-                            if target_trait_def_ids.contains(&def_id) {
-                                return true;
-                            }
-
-                            for p in self.rcx.tcx().super_predicates_of(def_id).predicates {
-                                if let PredicateKind::Trait(z) = p.0.kind().skip_binder() {
-                                    if target_trait_def_ids.contains(&z.trait_ref.def_id) {
-                                        return true;
-                                    }
-                                }
-                            }
-                        }
-                    }
+        where_predicates
+            .iter()
+            .any(|where_predicate| match where_predicate {
+                WherePredicate::BoundPredicate(x) => {
+                    self.trait_in_bounds_relaxed(target_trait_def_ids, x.bounds)
+                }
+                _ => false,
+            })
+    }
+
+    fn trait_in_bounds_relaxed(
+        &self,
+        target_trait_def_ids: &[DefId],
+        bounds: &[GenericBound],
+    ) -> bool {
+        bounds.iter().any(|bound| match bound {
+            GenericBound::Trait(x, ..) => match x.trait_ref.trait_def_id() {
+                Some(def_id) => self.trait_implies(target_trait_def_ids, def_id),
+                None => false,
+            },
+            _ => false,
+        })
+    }
+
+    /// Returns true if `trait_def_id` is one of the targets or has one as a (transitive) super-trait.
+    fn trait_implies(&self, target_trait_def_ids: &[DefId], trait_def_id: DefId) -> bool {
+        let tcx = self.rcx.tcx();
+        let mut visited = FxHashSet::default();
+        let mut work_list = vec![trait_def_id];
+        while let Some(def_id) = work_list.pop() {
+            if target_trait_def_ids.contains(&def_id) {
+                return true;
+            }
+            if !visited.insert(def_id) {
+                continue;
+            }
+            for p in tcx.super_predicates_of(def_id).predicates {
+                if let PredicateKind::Trait(x) = p.0.kind().skip_binder() {
+                    work_list.push(x.trait_ref.def_id);
                 }
             }
         }
-        return false;
+        false
+    }
+}
+
+fn has_type_params(generic_params: &[GenericParam]) -> bool {
+    generic_params
+        .iter()
+        .any(|generic_param| matches!(generic_param.kind, GenericParamKind::Type { .. }))
+}
+
+/// None of the generic parameters are bounded, so each type parameter of the ADT is flagged.
+fn relaxed_param_analyses(
+    tcx: TyCtxt<'_>,
+    adt_did: DefId,
+    analyses: BehaviorFlag,
+) -> FxHashMap<PostMapIdx, BehaviorFlag> {
+    tcx.generics_of(adt_did)
+        .params
+        .iter()
+        .filter(|param| matches!(param.kind, GenericParamDefKind::Type { .. }))
+        .map(|param| (PostMapIdx(param.index), analyses))
+        .collect()
+}
+
+/// Returns the DefId of the ADT that the impl is for.
+fn impl_adt_def_id(tcx: TyCtxt<'_>, impl_id: LocalDefId) -> Option<DefId> {
+    let trait_ref = tcx.impl_trait_ref(impl_id)?;
+    match trait_ref.self_ty().kind() {
+        ty::TyKind::Adt(adt_def, _) => Some(adt_def.did),
+        _ => None,
     }
 }
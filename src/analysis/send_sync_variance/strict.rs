@@ -4,14 +4,14 @@ use super::*;
 
 impl<'tcx> SendSyncVarianceChecker<'tcx> {
     /// Returns Some(DefId of ADT) if `impl Sync` for the ADT looks suspicious
-    /// (ADT: struct / enum / union)
+    /// (ADT: struct / enum / union), along with the analyses that flagged each generic param
     pub fn suspicious_sync(
         &mut self,
         impl_id: LocalDefId,
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
         copy_trait_def_id: DefId,
-    ) -> Option<(DefId, BehaviorFlag)> {
+    ) -> Option<SuspiciousImpl> {
         let rcx = self.rcx;
         let tcx = rcx.tcx();
         if let Some(trait_ref) = tcx.impl_trait_ref(impl_id) {
@@ -132,7 +132,7 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     if detected.is_empty() {
                         None
                     } else {
-                        Some((adt_did, detected))
+                        Some((adt_did, detected, need_send_sync))
                     }
                 };
             }
@@ -141,15 +141,16 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
     }
 
     /// Returns `Some(DefId of ADT)` if `impl Send` for the ADT looks suspicious
-    /// (ADT: struct / enum / union)
+    /// (ADT: struct / enum / union), along with the analyses that flagged each generic param
     pub fn suspicious_send(
         &mut self,
         impl_id: LocalDefId,
         send_trait_def_id: DefId,
         sync_trait_def_id: DefId,
         copy_trait_def_id: DefId,
-    ) -> Option<(DefId, BehaviorFlag)> {
-        let tcx = self.rcx.tcx();
+    ) -> Option<SuspiciousImpl> {
+        let rcx = self.rcx;
+        let tcx = rcx.tcx();
         if let Some(trait_ref) = tcx.impl_trait_ref(impl_id) {
             if let ty::TyKind::Adt(adt_def, impl_trait_substs) = trait_ref.self_ty().kind() {
                 let adt_did = adt_def.did;
@@ -166,6 +167,17 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     .entry(adt_did)
                     .or_insert_with(|| phantom_indices(tcx, adt_ty));
 
+                // Get `AdtBehavior` per generic parameter.
+                let adt_behavior = self
+                    .behavior_map
+                    .entry(adt_did)
+                    .or_insert_with(|| adt_behavior(rcx, adt_did));
+
+                // Generic params of pointer-like ADTs, which need to be both `Send` and `Sync`.
+                let mut ptr_like_params = FxHashSet::default();
+                let mut send_bounded = FxHashSet::default();
+                let mut sync_bounded = FxHashSet::default();
+
                 // If the below assertion fails, there must be an issue with librustc we're using.
                 // assert_eq!(tcx.generics_of(adt_did).params.len(), substs.len());
                 let generic_param_idx_map =
@@ -185,6 +197,11 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
 
                         analyses.insert(BehaviorFlag::PHANTOM_SEND_FOR_SEND);
                         analyses.insert(BehaviorFlag::RELAX_SEND);
+                        if let Some(behavior) = adt_behavior.get(&post_map_idx) {
+                            if behavior.is_ptr_like() {
+                                ptr_like_params.insert(post_map_idx);
+                            }
+                        }
                         need_send_sync.insert(post_map_idx, analyses);
                     }
                 }
@@ -217,6 +234,11 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                             let pre_map_idx = PreMapIdx(param_ty.index);
                             if let Some(mapped_idx) = generic_param_idx_map.get(&pre_map_idx) {
                                 let trait_did = trait_predicate.def_id();
+                                if trait_did == send_trait_def_id {
+                                    send_bounded.insert(*mapped_idx);
+                                } else if trait_did == sync_trait_def_id {
+                                    sync_bounded.insert(*mapped_idx);
+                                }
                                 if trait_did == send_trait_def_id
                                     || trait_did == sync_trait_def_id
                                     || trait_did == copy_trait_def_id
@@ -231,6 +253,18 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     }
                 }
 
+                // A shared pointer sends `&T` to other threads, so `T: Send` alone is not enough.
+                for post_map_idx in ptr_like_params {
+                    if !(send_bounded.contains(&post_map_idx)
+                        && sync_bounded.contains(&post_map_idx))
+                    {
+                        need_send_sync
+                            .entry(post_map_idx)
+                            .or_insert_with(BehaviorFlag::empty)
+                            .insert(BehaviorFlag::PTR_LIKE_SEND_FOR_SEND);
+                    }
+                }
+
                 return if need_send_sync.is_empty() {
                     None
                 } else {
@@ -241,7 +275,7 @@ impl<'tcx> SendSyncVarianceChecker<'tcx> {
                     if detected.is_empty() {
                         None
                     } else {
-                        Some((adt_did, detected))
+                        Some((adt_did, detected, need_send_sync))
                     }
                 };
             }
@@ -0,0 +1,197 @@
+//! Suggest the missing trait bounds of suspicious Send/Sync impls.
+
+use rustc_span::BytePos;
+
+use super::*;
+use crate::report::{SourceEdit, Suggestion};
+
+impl BehaviorFlag {
+    /// Analyses that are reported at the given report level.
+    pub fn reported_at(self, report_level: ReportLevel) -> BehaviorFlag {
+        let mut reported = BehaviorFlag::empty();
+        for bit in 0..u16::BITS {
+            let analysis = BehaviorFlag::from_bits_truncate(1 << bit);
+            if self.contains(analysis) && analysis.report_level() >= report_level {
+                reported.insert(analysis);
+            }
+        }
+        reported
+    }
+}
+
+/// Traits that a generic parameter flagged by `analyses` should be bounded by.
+fn required_traits(
+    impl_trait_did: DefId,
+    analyses: BehaviorFlag,
+    send_trait_did: DefId,
+    sync_trait_did: DefId,
+) -> Vec<DefId> {
+    let mut required = Vec::with_capacity(2);
+    if impl_trait_did == send_trait_did {
+        if analyses.contains(BehaviorFlag::PTR_LIKE_SEND_FOR_SEND) {
+            required.push(send_trait_did);
+            required.push(sync_trait_did);
+        } else if analyses.intersects(
+            BehaviorFlag::NAIVE_SEND_FOR_SEND
+                | BehaviorFlag::PHANTOM_SEND_FOR_SEND
+                | BehaviorFlag::RELAX_SEND,
+        ) {
+            required.push(send_trait_did);
+        }
+    } else if impl_trait_did == sync_trait_did {
+        if analyses.contains(BehaviorFlag::API_SEND_FOR_SYNC) {
+            required.push(send_trait_did);
+        }
+        if analyses.intersects(
+            BehaviorFlag::API_SYNC_FOR_SYNC
+                | BehaviorFlag::NAIVE_SYNC_FOR_SYNC
+                | BehaviorFlag::RELAX_SYNC,
+        ) {
+            required.push(sync_trait_did);
+        }
+    }
+    required
+}
+
+impl<'tcx> SendSyncVarianceChecker<'tcx> {
+    /// Traits that each generic parameter flagged by the `Send`/`Sync` analyses should be bounded by.
+    pub fn required_send_sync_bounds(
+        &self,
+        impl_id: LocalDefId,
+        param_analyses: &FxHashMap<PostMapIdx, BehaviorFlag>,
+        send_trait_did: DefId,
+        sync_trait_did: DefId,
+    ) -> Vec<(PostMapIdx, Vec<DefId>)> {
+        let impl_trait_did = match self.rcx.tcx().impl_trait_ref(impl_id) {
+            Some(trait_ref) => trait_ref.def_id,
+            None => return Vec::new(),
+        };
+
+        let mut required_bounds = param_analyses
+            .iter()
+            .map(|(&post_map_idx, &analyses)| {
+                (
+                    post_map_idx,
+                    required_traits(
+                        impl_trait_did,
+                        analyses.reported_at(self.rcx.report_level()),
+                        send_trait_did,
+                        sync_trait_did,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        required_bounds.sort_by_key(|(post_map_idx, _)| post_map_idx.0);
+        required_bounds
+    }
+
+    /// Suggest the impl header with the missing bounds added to its generic parameters.
+    /// e.g. `unsafe impl<T: Send> Send for Foo<T>` => `unsafe impl<T: Send + Sync> Send for Foo<T>`
+    pub fn suggest_bounds(
+        &self,
+        impl_id: LocalDefId,
+        required_bounds: &[(PostMapIdx, Vec<DefId>)],
+    ) -> Option<Suggestion> {
+        let tcx = self.rcx.tcx();
+        let hir = tcx.hir();
+        let item = hir.item(ItemId { def_id: impl_id });
+        if item.span.from_expansion() {
+            return None;
+        }
+        let (generics, self_ty) = match item.kind {
+            ItemKind::Impl(Impl {
+                ref generics,
+                self_ty,
+                ..
+            }) => (generics, self_ty),
+            _ => return None,
+        };
+
+        let trait_ref = tcx.impl_trait_ref(impl_id)?;
+        let impl_substs = match trait_ref.self_ty().kind() {
+            ty::TyKind::Adt(_, impl_substs) => impl_substs,
+            _ => return None,
+        };
+        let impl_generics = tcx.generics_of(impl_id);
+
+        // (param index, trait) pairs that are already bounded
+        let mut bounded = FxHashSet::default();
+        for atom in tcx
+            .param_env(impl_id)
+            .caller_bounds()
+            .iter()
+            .map(|x| x.kind().skip_binder())
+        {
+            if let PredicateKind::Trait(trait_predicate) = atom {
+                if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                    bounded.insert((param_ty.index, trait_predicate.def_id()));
+                }
+            }
+        }
+
+        let mut missing_bounds = Vec::new();
+        let mut insertions = Vec::new();
+        for (post_map_idx, required) in required_bounds {
+            // Concrete types in the impl (e.g. `Foo<i32>`) can't be bounded.
+            let param_ty = match impl_substs
+                .get(post_map_idx.0 as usize)
+                .map(|arg| arg.unpack())
+            {
+                Some(GenericArgKind::Type(ty)) => match ty.kind() {
+                    ty::TyKind::Param(param_ty) => *param_ty,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            let missing = required
+                .iter()
+                .copied()
+                .filter(|&trait_did| !bounded.contains(&(param_ty.index, trait_did)))
+                .map(|trait_did| tcx.item_name(trait_did).to_string())
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                continue;
+            }
+
+            let param_def_id = impl_generics.param_at(param_ty.index as usize, tcx).def_id;
+            let hir_param = generics
+                .params
+                .iter()
+                .find(|param| hir.local_def_id(param.hir_id).to_def_id() == param_def_id)?;
+
+            let missing = missing.join(" + ");
+            insertions.push(match hir_param.bounds_span() {
+                Some(bounds_span) => (bounds_span.shrink_to_hi(), format!(" + {}", missing)),
+                None => (hir_param.span.shrink_to_hi(), format!(": {}", missing)),
+            });
+            missing_bounds.push(format!("{}: {}", param_ty.name, missing));
+        }
+        if insertions.is_empty() {
+            return None;
+        }
+
+        // Rewrite the impl header, up to the where clause (if any).
+        insertions.sort_by_key(|(span, _)| span.lo());
+        let header_hi = if generics.where_clause.predicates.is_empty() {
+            self_ty.span.hi()
+        } else {
+            generics.where_clause.span.hi()
+        };
+        let header_span = item.span.with_hi(header_hi);
+        let mut rewritten = tcx.sess.source_map().span_to_snippet(header_span).ok()?;
+        for (span, insertion) in insertions.iter().rev() {
+            let BytePos(offset) = span.lo() - header_span.lo();
+            rewritten.insert_str(offset as usize, insertion);
+        }
+
+        Some(Suggestion::new(
+            format!("add the missing bounds `{}`", missing_bounds.join(", ")),
+            rewritten,
+            insertions
+                .into_iter()
+                .map(|(span, insertion)| SourceEdit::new(tcx, span, insertion))
+                .collect(),
+        ))
+    }
+}
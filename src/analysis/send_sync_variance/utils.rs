@@ -1,3 +1,6 @@
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_trait_selection::infer::InferCtxtExt;
+
 use super::*;
 
 // Note that len(adt_generics_iter) == len(substs_generics_iter)
@@ -151,6 +154,64 @@ pub fn borrowed_generic_params_in_ty<'tcx>(
         .map(|idx| PreMapIdx(idx))
 }
 
+// Within the inputs of the given function,
+// return the argument types of closures that the function may call with its own data.
+// e.g. `F` in `F: Fn(&T)`, `impl FnOnce(&T)`, `&dyn Fn(&T)`, `fn(&T)` => `(&T,)`
+pub fn closure_arg_tys_in_fn(tcx: TyCtxt<'_>, fn_did: DefId) -> Vec<Ty<'_>> {
+    let fn_sig = tcx.fn_sig(fn_did).skip_binder();
+    let mut input_params = FxHashSet::default();
+    let mut closure_arg_tys = Vec::new();
+
+    for input_ty in fn_sig.inputs() {
+        for node in input_ty.walk(tcx) {
+            if let GenericArgKind::Type(ty) = node.unpack() {
+                match ty.kind() {
+                    ty::TyKind::Param(param_ty) => {
+                        input_params.insert(param_ty.index);
+                    }
+                    ty::TyKind::FnPtr(fn_ptr_sig) => {
+                        closure_arg_tys.extend(fn_ptr_sig.skip_binder().inputs().iter().copied());
+                    }
+                    ty::TyKind::Dynamic(predicates, _) => {
+                        if let Some(principal) = predicates.principal() {
+                            if tcx
+                                .fn_trait_kind_from_lang_item(principal.def_id())
+                                .is_some()
+                            {
+                                closure_arg_tys.extend(principal.skip_binder().substs.types());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // `impl Fn(&T)` in argument position is desugared to a generic parameter as well.
+    for atom in tcx
+        .param_env(fn_did)
+        .caller_bounds()
+        .iter()
+        .map(|x| x.kind().skip_binder())
+    {
+        if let PredicateKind::Trait(trait_predicate) = atom {
+            if let ty::TyKind::Param(param_ty) = trait_predicate.self_ty().kind() {
+                if input_params.contains(&param_ty.index)
+                    && tcx
+                        .fn_trait_kind_from_lang_item(trait_predicate.def_id())
+                        .is_some()
+                {
+                    // trait_predicate => F: Fn<(&T,)>
+                    closure_arg_tys.extend(trait_predicate.trait_ref.substs.types().skip(1));
+                }
+            }
+        }
+    }
+
+    closure_arg_tys
+}
+
 const PSEUDO_OWNED: [&'static str; 4] = [
     "std::convert::Into",
     "core::convert::Into",
@@ -198,3 +259,22 @@ pub fn find_pseudo_owned_in_fn_ctxt<'tcx>(
 
     fn_ctxt_pseudo_owned_param_idx_map
 }
+
+// Check whether `ty` implements the trait (without generic parameters other than `Self`)
+// under the bounds of `param_env`.
+pub fn implements_trait<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    trait_did: DefId,
+    param_env: ty::ParamEnv<'tcx>,
+) -> bool {
+    let ty = tcx.erase_regions(ty);
+    if ty.has_escaping_bound_vars() {
+        return false;
+    }
+    tcx.infer_ctxt().enter(|infcx| {
+        infcx
+            .type_implements_trait(trait_did, ty, List::empty(), param_env)
+            .must_apply_modulo_regions()
+    })
+}
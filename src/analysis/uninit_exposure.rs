@@ -0,0 +1,172 @@
+//! Uninitialized buffer exposure detector
+//!
+//! Reports a buffer over uninitialized memory that is passed to a trait method
+//! whose implementation is provided by the user (e.g., `<R as Read>::read(&mut buf)`).
+//! A user-provided `Read` impl may read from the buffer before writing to it.
+use rustc_hir::BodyId;
+use rustc_middle::ty::{Instance, InstanceDef};
+use rustc_span::Span;
+
+use snafu::{Backtrace, Snafu};
+
+use crate::analysis::unsafe_dataflow::UninitBufferCollector;
+use crate::analysis::AnalysisKind;
+use crate::graph::Graph;
+use crate::prelude::*;
+use crate::report::{Report, ReportLevel, SpanRole};
+use crate::{ir, paths::*, utils};
+
+#[derive(Debug, Snafu)]
+pub enum UninitExposureError {
+    ResolveError { backtrace: Backtrace },
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UninitExposureError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UninitExposureError::*;
+        match self {
+            ResolveError { .. } => AnalysisErrorKind::OutOfScope,
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+/// An uninitialized buffer passed to a user-provided trait method
+struct Exposure {
+    method: String,
+    buffer_origin: String,
+    origin_span: Span,
+    call_span: Span,
+    report_level: ReportLevel,
+}
+
+pub struct UninitExposureChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> UninitExposureChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        UninitExposureChecker { rcx }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            for exposure in self.analyze_body(body_id) {
+                if exposure.report_level < self.rcx.report_level()
+                    || self.rcx.is_suppressed(
+                        "uninit_exposure",
+                        hir_map.body_owner_def_id(body_id).to_def_id(),
+                    )
+                {
+                    continue;
+                }
+
+                let mut color_span = unwrap_or!(
+                    utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                );
+                color_span.add_sub_span(SpanRole::UninitBuffer, exposure.origin_span);
+                color_span.add_sub_span(SpanRole::UnresolvableGenericCall, exposure.call_span);
+
+                rudra_report(Report::with_color_span(
+                    tcx,
+                    exposure.report_level,
+                    AnalysisKind::UninitExposure,
+                    format!(
+                        "Uninitialized buffer from `{}` is passed to `{}` in `{}`",
+                        exposure.buffer_origin,
+                        exposure.method,
+                        tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                    ),
+                    hir_map.body_owner_def_id(body_id).to_def_id(),
+                    &color_span,
+                ))
+            }
+        }
+    }
+
+    fn analyze_body(&self, body_id: BodyId) -> Vec<Exposure> {
+        let tcx = self.rcx.tcx();
+        let body_did = tcx.hir().body_owner_def_id(body_id).to_def_id();
+        let translated_body = self.rcx.translate_body(body_did);
+        let body = match translated_body.as_ref() {
+            Ok(body) => body,
+            Err(e) => {
+                // MIR is not available for def - log it and continue
+                e.log();
+                return Vec::new();
+            }
+        };
+
+        let collector = UninitBufferCollector::new(self.rcx, body);
+        let buffers = collector.collect();
+        if buffers.is_empty() {
+            return Vec::new();
+        }
+
+        let param_env = tcx.param_env(body_did);
+        let mut exposures = Vec::new();
+        for (id, terminator) in body.terminators().enumerate() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                callee_substs,
+                ref args,
+                ..
+            } = terminator.kind
+            {
+                let trait_did = match tcx.trait_of_item(callee_did) {
+                    Some(trait_did) => trait_did,
+                    None => continue,
+                };
+
+                // Only the implementations that are not known statically are user-provided
+                let user_provided =
+                    match Instance::resolve(tcx, param_env, callee_did, callee_substs) {
+                        Err(_e) => {
+                            log_err!(ResolveError);
+                            false
+                        }
+                        Ok(Some(instance)) => matches!(instance.def, InstanceDef::Virtual(..)),
+                        Ok(None) => true,
+                    };
+                if !user_provided {
+                    continue;
+                }
+
+                let exposed = buffers.iter().find(|buffer| {
+                    buffer.block != id
+                        && body.reaches(buffer.block, id)
+                        && args
+                            .iter()
+                            .any(|arg| collector.root_of(arg) == Some(buffer.root))
+                });
+                if let Some(buffer) = exposed {
+                    let ext = tcx.ext();
+                    let report_level = if ext.match_def_path(trait_did, &IO_READ)
+                        || ext.match_def_path(trait_did, &IO_BUF_READ)
+                    {
+                        ReportLevel::Error
+                    } else {
+                        ReportLevel::Warning
+                    };
+                    exposures.push(Exposure {
+                        method: tcx.def_path_str(callee_did),
+                        buffer_origin: tcx.def_path_str(buffer.origin),
+                        origin_span: body.basic_blocks[buffer.block]
+                            .terminator
+                            .original
+                            .source_info
+                            .span,
+                        call_span: terminator.original.source_info.span,
+                        report_level,
+                    });
+                }
+            }
+        }
+        exposures
+    }
+}
@@ -0,0 +1,240 @@
+//! Detection of drop guards that restore invariants during unwinding,
+//! such as `SetLenOnDrop` in `Vec::extend` or `InsertionHole` in `insertion_sort`.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::DefId;
+use rustc_index::vec::Idx;
+use rustc_middle::mir::{Local, Operand, Place, ProjectionElem, Rvalue, StatementKind};
+use rustc_middle::ty::Ty;
+
+use super::place::{collect_derived_places, BasePlace};
+use super::*;
+use crate::graph::Graph;
+
+/// A local whose `Drop` impl writes back to the container of a lifetime bypass.
+pub struct DropGuard<'tcx> {
+    pub local: Local,
+    pub ty: Ty<'tcx>,
+}
+
+impl<'tcx> DropGuard<'tcx> {
+    /// Span of the guard variable declaration
+    pub fn span(&self, body: &ir::Body<'tcx>) -> Span {
+        body.original.local_decls[self.local].source_info.span
+    }
+}
+
+pub struct DropGuardDetector<'a, 'tcx> {
+    rcx: RudraCtxt<'tcx>,
+    body: &'a ir::Body<'tcx>,
+    /// Place that each local was derived from (`_2 = &mut ((*_1).1)`, `_3 = move _2 as *mut T`, ...)
+    derived_from: Vec<Option<Place<'tcx>>>,
+}
+
+impl<'a, 'tcx> DropGuardDetector<'a, 'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+        DropGuardDetector {
+            rcx,
+            body,
+            derived_from: collect_derived_places(body),
+        }
+    }
+
+    /// Returns a guard that is built from the container of the lifetime bypasses
+    /// and dropped on the unwind path of every sink.
+    pub fn find_guard(&self, sources: &[usize], sinks: &[usize]) -> Option<DropGuard<'tcx>> {
+        if sinks.is_empty() {
+            return None;
+        }
+
+        let mut bypass_places = FxHashSet::default();
+        for &id in sources {
+            if let ir::TerminatorKind::StaticCall { args, .. } =
+                &self.body.basic_blocks[id].terminator.kind
+            {
+                for place in args.iter().filter_map(|arg| arg.place()) {
+                    bypass_places.insert(self.base_place(place));
+                }
+            }
+        }
+
+        for (local, targets) in self.guard_candidates() {
+            // The guard writes back to the bypassed place, or the bypass goes through the guard
+            let restores_bypass = bypass_places.iter().any(|bypass_place| {
+                bypass_place.local == local
+                    || targets.iter().any(|target| target.overlaps(bypass_place))
+            });
+            if restores_bypass && sinks.iter().all(|&sink| self.is_guarded(local, sink)) {
+                return Some(DropGuard {
+                    local,
+                    ty: self.body.local_decls[local.index()].ty,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Locals of a restoring guard type, with the places that the pointers they are built from point to.
+    fn guard_candidates(&self) -> FxHashMap<Local, FxHashSet<BasePlace>> {
+        let mut candidates: FxHashMap<Local, FxHashSet<BasePlace>> = FxHashMap::default();
+        for (idx, local_decl) in self.body.local_decls.iter().enumerate() {
+            if let Some(adt_def) = local_decl.ty.ty_adt_def() {
+                if self.restores_state(adt_def.did) {
+                    candidates.insert(Local::new(idx), FxHashSet::default());
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        for block in self.body.basic_blocks.iter() {
+            for statement in block.statements.iter() {
+                // Struct expressions are split into field assignments (`(_4.0: &mut usize) = move _6`)
+                if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                    if let Some(targets) = candidates.get_mut(&lhs.local) {
+                        match rvalue {
+                            Rvalue::Aggregate(_, operands) => {
+                                self.collect_targets(targets, operands)
+                            }
+                            Rvalue::Use(operand) => {
+                                self.collect_targets(targets, std::slice::from_ref(operand))
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+
+            // Guards built with a constructor function (`Guard::new(&mut v)`)
+            if let ir::TerminatorKind::StaticCall {
+                args,
+                destination: Some((place, _)),
+                ..
+            } = &block.terminator.kind
+            {
+                if let Some(targets) = candidates.get_mut(&place.local) {
+                    self.collect_targets(targets, args);
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Collects the places behind the pointer operands, which the guard can write back to.
+    /// Plain data such as `local_len: self.len` is copied into the guard and not written back.
+    fn collect_targets(&self, targets: &mut FxHashSet<BasePlace>, operands: &[Operand<'tcx>]) {
+        for operand in operands {
+            if let Some(place) = operand.place() {
+                let ty = place.ty(self.body, self.rcx.tcx()).ty;
+                if matches!(
+                    ty.kind(),
+                    TyKind::Ref(..) | TyKind::RawPtr(_) | TyKind::Adt(..)
+                ) {
+                    targets.insert(self.base_place(place));
+                }
+            }
+        }
+    }
+
+    fn base_place(&self, place: Place<'tcx>) -> BasePlace {
+        BasePlace::new(&self.derived_from, place)
+    }
+
+    /// Returns true if the ADT has a local `Drop` impl that writes through its pointers.
+    fn restores_state(&self, adt_did: DefId) -> bool {
+        let destructor = match self.rcx.tcx().adt_destructor(adt_did) {
+            Some(destructor) if destructor.did.is_local() => destructor,
+            _ => return false,
+        };
+
+        match self.rcx.translate_body(destructor.did).as_ref() {
+            Ok(drop_body) => writes_back(self.rcx, drop_body),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if `guard` is dropped when the sink unwinds.
+    fn is_guarded(&self, guard: Local, sink: usize) -> bool {
+        let block = &self.body.basic_blocks[sink];
+        match &block.terminator.kind {
+            // The guard itself runs its drop glue
+            ir::TerminatorKind::Drop { place, .. } if place.local == guard => return true,
+            _ => (),
+        }
+
+        if block.is_cleanup {
+            // Already unwinding; the guard is dropped either before or after the sink
+            return self.guard_drops(guard).into_iter().any(|guard_drop| {
+                self.body.reaches(sink, guard_drop) || self.body.reaches(guard_drop, sink)
+            });
+        }
+
+        let unwind = match block.terminator.kind {
+            ir::TerminatorKind::StaticCall { cleanup, .. }
+            | ir::TerminatorKind::FnPtr { cleanup, .. } => cleanup,
+            ir::TerminatorKind::Drop { unwind, .. } => unwind,
+            _ => None,
+        };
+        match unwind {
+            Some(unwind) => self
+                .guard_drops(guard)
+                .into_iter()
+                .any(|guard_drop| self.body.reaches(unwind, guard_drop)),
+            None => false,
+        }
+    }
+
+    /// Cleanup blocks that drop `guard`.
+    fn guard_drops(&self, guard: Local) -> Vec<usize> {
+        self.body
+            .basic_blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| {
+                block.is_cleanup
+                    && matches!(
+                        &block.terminator.kind,
+                        ir::TerminatorKind::Drop { place, .. } if place.local == guard
+                    )
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+/// Checks whether a `Drop` impl writes to memory that is not owned by the guard itself,
+/// either by a store through a pointer field or by a raw memory write.
+fn writes_back<'tcx>(rcx: RudraCtxt<'tcx>, drop_body: &ir::Body<'tcx>) -> bool {
+    let self_local = Local::from_u32(1);
+    for block in drop_body.basic_blocks.iter() {
+        for statement in block.statements.iter() {
+            if let StatementKind::Assign(box (lhs, _)) = &statement.kind {
+                let num_deref = lhs
+                    .projection
+                    .iter()
+                    .filter(|elem| matches!(elem, ProjectionElem::Deref))
+                    .count();
+                // `self` is `&mut Self`, so the first deref of `self` stays inside the guard
+                if (lhs.local == self_local && num_deref >= 2)
+                    || (lhs.local != self_local && num_deref >= 1)
+                {
+                    return true;
+                }
+            }
+        }
+
+        if let ir::TerminatorKind::StaticCall { callee_did, .. } = block.terminator.kind {
+            let ext = rcx.tcx().ext();
+            let symbol_vec = ext.get_def_path(callee_did);
+            if paths::STRONG_LIFETIME_BYPASS_LIST.contains(&symbol_vec)
+                || ext.match_def_path(callee_did, &PTR_WRITE)
+                || ext.match_def_path(callee_did, &PTR_DIRECT_WRITE)
+            {
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,793 @@
+mod guard;
+mod place;
+mod range;
+mod summary;
+mod uninit;
+
+use std::collections::BTreeSet;
+
+use rustc_hir::{def_id::DefId, BodyId};
+use rustc_middle::mir::{Local, Operand};
+use rustc_middle::ty::{
+    subst::GenericArgKind, Instance, InstanceDef, ParamEnv, Ty, TyCtxt, TyKind, TypeFoldable,
+};
+use rustc_span::{Span, DUMMY_SP};
+
+use snafu::{Backtrace, Snafu};
+
+use crate::graph::GraphTaint;
+use crate::prelude::*;
+use crate::{
+    analysis::{AnalysisKind, IntoReportLevel},
+    graph::TaintAnalyzer,
+    ir,
+    paths::{self, *},
+    report::{Report, ReportLevel, SpanRole},
+    utils,
+    visitor::ContainsUnsafe,
+};
+
+pub use guard::{DropGuard, DropGuardDetector};
+pub use place::{collect_origins, origin_chain, PlaceTaintAnalyzer, UnwindPath};
+pub use range::{BoundBase, RangeAnalyzer, UpperBound};
+pub use summary::{FnSummary, SummaryCache};
+pub use uninit::{UninitBuffer, UninitBufferCollector};
+
+#[derive(Debug, Snafu)]
+pub enum UnsafeDataflowError {
+    PushPopBlock { backtrace: Backtrace },
+    ResolveError { backtrace: Backtrace },
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UnsafeDataflowError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UnsafeDataflowError::*;
+        match self {
+            PushPopBlock { .. } => AnalysisErrorKind::Unreachable,
+            ResolveError { .. } => AnalysisErrorKind::OutOfScope,
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+/// Determines how taint is propagated from lifetime bypasses to sinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeDataflowMode {
+    /// Reports if a lifetime bypass can reach a sink in the control flow graph.
+    Reachability,
+    /// Reports only if the bypassed value (or a value aliasing it) is still alive at a sink.
+    PlaceSensitive,
+    /// Reports only if the unwind path of a sink drops the bypassed value.
+    Unwind,
+}
+
+pub struct UnsafeDataflowChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+    mode: UnsafeDataflowMode,
+}
+
+impl<'tcx> UnsafeDataflowChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>, mode: UnsafeDataflowMode) -> Self {
+        UnsafeDataflowChecker { rcx, mode }
+    }
+
+    pub fn analyze(self) {
+        let tcx = self.rcx.tcx();
+        let hir_map = tcx.hir();
+        let summaries = SummaryCache::new(self.rcx, self.mode);
+
+        // Iterates all (type, related function) pairs
+        for (_ty_hir_id, (body_id, related_item_span)) in self.rcx.types_with_related_items() {
+            if let Some(status) =
+                inner::UnsafeDataflowBodyAnalyzer::analyze_body(self.rcx, &summaries, body_id)
+            {
+                let behavior_flag = status.behavior_flag();
+                // A drop guard restores the state during unwinding, so the report is downgraded
+                let report_level = if status.drop_guard().is_some() {
+                    ReportLevel::Info
+                } else {
+                    behavior_flag.report_level()
+                };
+                if !behavior_flag.is_empty()
+                    && report_level >= self.rcx.report_level()
+                    && !self.rcx.is_suppressed(
+                        "unsafe_dataflow",
+                        hir_map.body_owner_def_id(body_id).to_def_id(),
+                    )
+                {
+                    let mut color_span = unwrap_or!(
+                        utils::ColorSpan::new(tcx, related_item_span).context(InvalidSpan) => continue
+                    );
+
+                    for &span in status.strong_bypass_spans() {
+                        color_span.add_sub_span(SpanRole::StrongBypass, span);
+                    }
+
+                    for &span in status.weak_bypass_spans() {
+                        color_span.add_sub_span(SpanRole::WeakBypass, span);
+                    }
+
+                    for &span in status.unresolvable_generic_function_spans() {
+                        color_span.add_sub_span(SpanRole::UnresolvableGenericCall, span);
+                    }
+
+                    for &span in status.dynamic_call_spans() {
+                        color_span.add_sub_span(SpanRole::DynamicCall, span);
+                    }
+
+                    if let Some((_, span)) = status.drop_guard() {
+                        color_span.add_sub_span(SpanRole::DropGuard, *span);
+                    }
+
+                    // Drops on the unwind path (the first span is the sink itself)
+                    for (spans, _) in status.unwind_paths() {
+                        for &span in spans.iter().skip(1) {
+                            color_span.add_sub_span(SpanRole::UnwindDrop, span);
+                        }
+                    }
+
+                    let mut description = format!(
+                        "Potential unsafe dataflow issue in `{}`",
+                        tcx.def_path_str(hir_map.body_owner_def_id(body_id).to_def_id())
+                    );
+                    if behavior_flag.contains(BehaviorFlag::USER_DEFINED)
+                        && !status.user_categories().is_empty()
+                    {
+                        let categories = status
+                            .user_categories()
+                            .iter()
+                            .map(String::as_str)
+                            .collect::<Vec<_>>();
+                        description += &format!(" (user-defined: {})", categories.join(", "));
+                    }
+                    if !status.unwind_paths().is_empty() {
+                        let source_map = tcx.sess.source_map();
+                        let paths = status
+                            .unwind_paths()
+                            .iter()
+                            .map(|(spans, to_caller)| {
+                                let mut steps = spans
+                                    .iter()
+                                    .map(|span| {
+                                        let loc = source_map.lookup_char_pos(span.lo());
+                                        format!("{}:{}", loc.line, loc.col.0 + 1)
+                                    })
+                                    .collect::<Vec<_>>();
+                                if *to_caller {
+                                    steps.push("caller".to_owned());
+                                }
+                                steps.join(" -> ")
+                            })
+                            .collect::<Vec<_>>();
+                        description += &format!(" (unwind path: {})", paths.join(", "));
+                    }
+                    if let Some((guard_ty, _)) = status.drop_guard() {
+                        description += &format!(
+                            " (guarded by `{}`, whose `Drop` impl restores the state on unwinding)",
+                            guard_ty
+                        );
+                    }
+
+                    rudra_report(Report::with_color_span(
+                        tcx,
+                        report_level,
+                        AnalysisKind::UnsafeDataflow(behavior_flag),
+                        description,
+                        hir_map.body_owner_def_id(body_id).to_def_id(),
+                        &color_span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if dropping `ty` may run a `Drop` impl that cannot be resolved here.
+pub fn has_unresolvable_drop<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ty: Ty<'tcx>,
+) -> bool {
+    ty.needs_drop(tcx, param_env)
+        && ty.walk(tcx).any(|arg| match arg.unpack() {
+            GenericArgKind::Type(ty) => matches!(
+                ty.kind(),
+                TyKind::Param(_) | TyKind::Projection(_) | TyKind::Opaque(..) | TyKind::Dynamic(..)
+            ),
+            _ => false,
+        })
+}
+
+mod inner {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct UnsafeDataflowStatus {
+        strong_bypasses: Vec<Span>,
+        weak_bypasses: Vec<Span>,
+        unresolvable_generic_functions: Vec<Span>,
+        /// Calls through trait objects and function pointers
+        dynamic_calls: Vec<Span>,
+        /// Categories of the user-defined bypasses in the body
+        user_categories: BTreeSet<String>,
+        /// Spans of the calls and drops from a sink to a drop of the bypassed value,
+        /// and whether the path unwinds into the caller
+        unwind_paths: Vec<(Vec<Span>, bool)>,
+        /// Type and span of a drop guard that protects every sink
+        drop_guard: Option<(String, Span)>,
+        behavior_flag: BehaviorFlag,
+        summary: FnSummary,
+    }
+
+    impl UnsafeDataflowStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn summary(&self) -> FnSummary {
+            self.summary
+        }
+
+        pub fn strong_bypass_spans(&self) -> &Vec<Span> {
+            &self.strong_bypasses
+        }
+
+        pub fn weak_bypass_spans(&self) -> &Vec<Span> {
+            &self.weak_bypasses
+        }
+
+        pub fn unresolvable_generic_function_spans(&self) -> &Vec<Span> {
+            &self.unresolvable_generic_functions
+        }
+
+        pub fn dynamic_call_spans(&self) -> &Vec<Span> {
+            &self.dynamic_calls
+        }
+
+        pub fn unwind_paths(&self) -> &Vec<(Vec<Span>, bool)> {
+            &self.unwind_paths
+        }
+
+        pub fn drop_guard(&self) -> Option<&(String, Span)> {
+            self.drop_guard.as_ref()
+        }
+
+        pub fn user_categories(&self) -> &BTreeSet<String> {
+            &self.user_categories
+        }
+    }
+
+    /// Marks sources and sinks on both block-level and place-level taint analyzers,
+    /// and propagates the taint according to the analysis mode.
+    struct Taints<'a, 'tcx> {
+        block: TaintAnalyzer<'a, ir::Body<'tcx>, BehaviorFlag>,
+        place: PlaceTaintAnalyzer<'a, 'tcx>,
+        sources: Vec<usize>,
+        sinks: Vec<usize>,
+    }
+
+    impl<'a, 'tcx> Taints<'a, 'tcx> {
+        fn new(tcx: TyCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+            Taints {
+                block: TaintAnalyzer::new(body),
+                place: PlaceTaintAnalyzer::new(tcx, body),
+                sources: Vec::new(),
+                sinks: Vec::new(),
+            }
+        }
+
+        fn mark_source(&mut self, id: usize, taint: &BehaviorFlag) {
+            self.block.mark_source(id, taint);
+            self.place.mark_source(id, taint);
+            self.sources.push(id);
+        }
+
+        fn mark_sink(&mut self, id: usize) {
+            self.block.mark_sink(id);
+            self.place.mark_sink(id);
+            self.sinks.push(id);
+        }
+
+        fn mark_consumer(&mut self, id: usize) {
+            self.place.mark_consumer(id);
+        }
+
+        fn propagate(&self, mode: UnsafeDataflowMode) -> (BehaviorFlag, Vec<UnwindPath>) {
+            match mode {
+                UnsafeDataflowMode::Reachability => (self.block.propagate(), Vec::new()),
+                UnsafeDataflowMode::PlaceSensitive => (self.place.propagate(), Vec::new()),
+                UnsafeDataflowMode::Unwind => self.place.propagate_unwind(),
+            }
+        }
+    }
+
+    pub struct UnsafeDataflowBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        summaries: &'a SummaryCache<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        status: UnsafeDataflowStatus,
+    }
+
+    impl<'a, 'tcx> UnsafeDataflowBodyAnalyzer<'a, 'tcx> {
+        pub fn new(
+            rcx: RudraCtxt<'tcx>,
+            summaries: &'a SummaryCache<'tcx>,
+            param_env: ParamEnv<'tcx>,
+            body: &'a ir::Body<'tcx>,
+        ) -> Self {
+            UnsafeDataflowBodyAnalyzer {
+                rcx,
+                summaries,
+                body,
+                param_env,
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze_body(
+            rcx: RudraCtxt<'tcx>,
+            summaries: &'a SummaryCache<'tcx>,
+            body_id: BodyId,
+        ) -> Option<UnsafeDataflowStatus> {
+            let hir_map = rcx.tcx().hir();
+            let body_did = hir_map.body_owner_def_id(body_id).to_def_id();
+
+            if rcx.tcx().ext().match_def_path(
+                body_did,
+                &["rudra_paths_discovery", "PathsDiscovery", "discover"],
+            ) {
+                // Special case for paths discovery
+                trace_calls_in_body(rcx, body_did);
+                None
+            } else {
+                let contains_unsafe = ContainsUnsafe::contains_unsafe(rcx.tcx(), body_id);
+                match rcx.translate_body(body_did).as_ref() {
+                    Err(e) => {
+                        // MIR is not available for def - log it and continue
+                        e.log();
+                        None
+                    }
+                    Ok(body) => {
+                        let param_env = rcx.tcx().param_env(body_did);
+                        let body_analyzer =
+                            UnsafeDataflowBodyAnalyzer::new(rcx, summaries, param_env, body);
+                        // Safe functions are only analyzed
+                        // if they call local helpers that contain lifetime bypasses.
+                        if contains_unsafe || body_analyzer.calls_bypassing_local_fn() {
+                            Some(body_analyzer.analyze())
+                        } else {
+                            None
+                        }
+                    }
+                }
+            }
+        }
+
+        pub fn analyze(mut self) -> UnsafeDataflowStatus {
+            let mut taints = Taints::new(self.rcx.tcx(), self.body);
+            let ranges = RangeAnalyzer::new(self.rcx, self.body, self.param_env);
+            let uninit_buffers = UninitBufferCollector::new(self.rcx, self.body);
+
+            let mut generic_drops = Vec::new();
+            for (id, terminator) in self.body.terminators().enumerate() {
+                match terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        callee_substs,
+                        ref args,
+                        ..
+                    } => {
+                        let tcx = self.rcx.tcx();
+                        let ext = tcx.ext();
+                        // Check for lifetime bypass
+                        let symbol_vec = ext.get_def_path(callee_did);
+                        if paths::OWNERSHIP_CONSUMER_LIST.contains(&symbol_vec) {
+                            taints.mark_consumer(id);
+                        }
+
+                        if paths::STRONG_LIFETIME_BYPASS_LIST.contains(&symbol_vec) {
+                            if self.fn_called_on_copy(
+                                (callee_did, args),
+                                &[&PTR_READ[..], &PTR_DIRECT_READ[..]],
+                            ) {
+                                // read on Copy types is not a lifetime bypass.
+                                continue;
+                            }
+
+                            if ext.match_def_path(callee_did, &MAYBE_UNINIT_ASSUME_INIT)
+                                && !uninit_buffers.is_uninit_assume_init(args)
+                            {
+                                // The value may have been initialized with `write()` or `as_mut_ptr()`
+                                continue;
+                            }
+
+                            if ext.match_def_path(callee_did, &VEC_SET_LEN)
+                                && ranges.is_non_growing_set_len(id, args)
+                            {
+                                // Leaking data is safe (`vec.set_len(0);`, `vec.set_len(len - 1);`)
+                                continue;
+                            }
+
+                            let mut bypass_flag = *STRONG_BYPASS_MAP.get(&symbol_vec).unwrap();
+                            if ext.match_def_path(callee_did, &VEC_SET_LEN)
+                                && uninit_buffers.is_uninit_vec_set_len(id, args)
+                            {
+                                // `Vec::with_capacity()` followed by `set_len()`
+                                bypass_flag |= BehaviorFlag::UNINIT;
+                            }
+                            taints.mark_source(id, &bypass_flag);
+                            self.status.summary.strong_bypass |= bypass_flag;
+                            self.status
+                                .strong_bypasses
+                                .push(terminator.original.source_info.span);
+                            if let Some(category) = USER_BYPASS_CATEGORY_MAP.get(&symbol_vec) {
+                                self.status.user_categories.insert(category.clone());
+                            }
+                        } else if paths::WEAK_LIFETIME_BYPASS_LIST.contains(&symbol_vec) {
+                            if self.fn_called_on_copy(
+                                (callee_did, args),
+                                &[&PTR_WRITE[..], &PTR_DIRECT_WRITE[..]],
+                            ) {
+                                // writing Copy types is not a lifetime bypass.
+                                continue;
+                            }
+
+                            if (ext.match_def_path(callee_did, &SLICE_FROM_RAW_PARTS)
+                                || ext.match_def_path(callee_did, &SLICE_FROM_RAW_PARTS_MUT)
+                                || ext.match_def_path(callee_did, &PTR_SLICE_FROM_RAW_PARTS)
+                                || ext.match_def_path(callee_did, &PTR_SLICE_FROM_RAW_PARTS_MUT))
+                                && ranges.is_in_bounds_from_raw_parts(id, args)
+                            {
+                                // A slice within the length of its container (`from_raw_parts(v.as_ptr(), v.len())`)
+                                continue;
+                            }
+
+                            let bypass_flag = WEAK_BYPASS_MAP.get(&symbol_vec).unwrap();
+                            taints.mark_source(id, bypass_flag);
+                            self.status.summary.weak_bypass |= *bypass_flag;
+                            self.status
+                                .weak_bypasses
+                                .push(terminator.original.source_info.span);
+                            if let Some(category) = USER_BYPASS_CATEGORY_MAP.get(&symbol_vec) {
+                                self.status.user_categories.insert(category.clone());
+                            }
+                        } else if paths::GENERIC_FN_LIST.contains(&symbol_vec) {
+                            taints.mark_sink(id);
+                            self.status.summary.calls_unresolvable_generic = true;
+                            self.status
+                                .unresolvable_generic_functions
+                                .push(terminator.original.source_info.span);
+                        } else {
+                            // Check for unresolvable generic function calls
+                            match Instance::resolve(
+                                self.rcx.tcx(),
+                                self.param_env,
+                                callee_did,
+                                callee_substs,
+                            ) {
+                                Err(_e) => log_err!(ResolveError),
+                                Ok(Some(instance)) => {
+                                    // Calls were successfully resolved,
+                                    // apply the summary if the callee is a local function
+                                    match instance.def {
+                                        InstanceDef::Item(def) if def.did.is_local() => {
+                                            self.apply_summary(
+                                                &mut taints,
+                                                id,
+                                                terminator,
+                                                instance,
+                                            );
+                                        }
+                                        InstanceDef::Virtual(..) => {
+                                            // Calls through a vtable of a trait object
+                                            self.mark_dynamic_call(&mut taints, id, terminator);
+                                        }
+                                        _ => (),
+                                    }
+                                }
+                                Ok(None) => {
+                                    // Call contains unresolvable generic parts
+                                    // Here, we are making a two step approximation:
+                                    // 1. Unresolvable generic code is potentially user-provided
+                                    // 2. User-provided code potentially panics
+                                    taints.mark_sink(id);
+                                    self.status.summary.calls_unresolvable_generic = true;
+                                    self.status
+                                        .unresolvable_generic_functions
+                                        .push(terminator.original.source_info.span);
+                                }
+                            }
+                        }
+                    }
+                    ir::TerminatorKind::FnPtr { .. } => {
+                        self.mark_dynamic_call(&mut taints, id, terminator);
+                    }
+                    ir::TerminatorKind::Drop { ty, .. } => {
+                        // Drop glue of generic types calls user-provided `Drop` impls,
+                        // which may panic or observe the duplicated value
+                        if has_unresolvable_drop(self.rcx.tcx(), self.param_env, ty) {
+                            generic_drops.push(id);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            // Place-sensitive modes track the dropped place themselves, but reachability
+            // only knows blocks, so a drop is a sink only if it may drop the bypassed value
+            let origins = collect_origins(self.body);
+            for id in generic_drops {
+                if self.summaries.mode() != UnsafeDataflowMode::Reachability
+                    || self.drops_bypassed_value(&origins, &taints.sources, id)
+                {
+                    taints.mark_sink(id);
+                    self.status.summary.calls_unresolvable_generic = true;
+                    self.status.unresolvable_generic_functions.push(
+                        self.body.basic_blocks[id]
+                            .terminator
+                            .original
+                            .source_info
+                            .span,
+                    );
+                }
+            }
+
+            let (behavior_flag, unwind_paths) = taints.propagate(self.summaries.mode());
+            self.status.behavior_flag = behavior_flag;
+            self.status.unwind_paths = unwind_paths
+                .into_iter()
+                .map(|path| {
+                    let spans = path
+                        .blocks
+                        .iter()
+                        .map(|&id| &self.body.basic_blocks[id].terminator)
+                        .filter(|terminator| {
+                            // Only calls and drops are interesting steps of the path
+                            !matches!(
+                                terminator.kind,
+                                ir::TerminatorKind::Goto(_) | ir::TerminatorKind::Unimplemented(_)
+                            )
+                        })
+                        .map(|terminator| terminator.original.source_info.span)
+                        .collect();
+                    (spans, path.to_caller)
+                })
+                .collect();
+            if !self.status.behavior_flag.is_empty() {
+                let detector = DropGuardDetector::new(self.rcx, self.body);
+                if let Some(guard) = detector.find_guard(&taints.sources, &taints.sinks) {
+                    self.status.drop_guard = Some((guard.ty.to_string(), guard.span(self.body)));
+                }
+            }
+            self.status
+        }
+
+        /// Returns true if the place dropped by `drop_id` shares its root local
+        /// with an argument or the return value of a lifetime bypass.
+        fn drops_bypassed_value(
+            &self,
+            origins: &[Option<Local>],
+            sources: &[usize],
+            drop_id: usize,
+        ) -> bool {
+            let root = |local: Local| place::find_root(origins, local).unwrap_or(local);
+            let dropped = match &self.body.basic_blocks[drop_id].terminator.kind {
+                ir::TerminatorKind::Drop { place, .. } => root(place.local),
+                _ => return false,
+            };
+
+            sources
+                .iter()
+                .any(|&id| match &self.body.basic_blocks[id].terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        args, destination, ..
+                    } => args
+                        .iter()
+                        .filter_map(|arg| arg.place())
+                        .chain(destination.iter().map(|(place, _)| *place))
+                        .any(|place| root(place.local) == dropped),
+                    _ => false,
+                })
+        }
+
+        /// Returns true if the body calls a local function whose summary has a lifetime bypass.
+        fn calls_bypassing_local_fn(&self) -> bool {
+            self.body
+                .terminators()
+                .any(|terminator| match terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        callee_substs,
+                        ..
+                    } => match Instance::resolve(
+                        self.rcx.tcx(),
+                        self.param_env,
+                        callee_did,
+                        callee_substs,
+                    ) {
+                        Ok(Some(Instance {
+                            def: InstanceDef::Item(def),
+                            ..
+                        })) if def.did.is_local() => {
+                            !self.summaries.summary(def.did).bypass_flag().is_empty()
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                })
+        }
+
+        /// Applies the summary of a resolved local callee to the call site.
+        fn apply_summary(
+            &mut self,
+            taints: &mut Taints<'a, 'tcx>,
+            id: usize,
+            terminator: &ir::Terminator<'tcx>,
+            instance: Instance<'tcx>,
+        ) {
+            let summary = self.summaries.summary(instance.def_id());
+            let span = terminator.original.source_info.span;
+
+            if !summary.bypass_flag().is_empty() {
+                // If the callee both bypasses lifetime and calls generic code,
+                // the callee itself is responsible for the ordering between them.
+                taints.mark_source(id, &summary.bypass_flag());
+                self.status.summary.strong_bypass |= summary.strong_bypass;
+                self.status.summary.weak_bypass |= summary.weak_bypass;
+                if summary.strong_bypass.is_empty() {
+                    self.status.weak_bypasses.push(span);
+                } else {
+                    self.status.strong_bypasses.push(span);
+                }
+            } else if summary.calls_dynamic {
+                taints.mark_sink(id);
+                self.status.summary.calls_dynamic = true;
+                self.status.dynamic_calls.push(span);
+            } else if summary.calls_unresolvable_generic
+                && instance
+                    .substs
+                    .definitely_has_param_types_or_consts(self.rcx.tcx())
+            {
+                // Generic code in the callee is only unresolvable
+                // if it is instantiated with our own generic parameters.
+                taints.mark_sink(id);
+                self.status.summary.calls_unresolvable_generic = true;
+                self.status.unresolvable_generic_functions.push(span);
+            }
+        }
+
+        /// Calls whose target is only known at runtime potentially run user-provided code.
+        fn mark_dynamic_call(
+            &mut self,
+            taints: &mut Taints<'a, 'tcx>,
+            id: usize,
+            terminator: &ir::Terminator<'tcx>,
+        ) {
+            taints.mark_sink(id);
+            self.status.summary.calls_dynamic = true;
+            self.status
+                .dynamic_calls
+                .push(terminator.original.source_info.span);
+        }
+
+        fn fn_called_on_copy(
+            &self,
+            (callee_did, callee_args): (DefId, &Vec<Operand<'tcx>>),
+            paths: &[&[&str]],
+        ) -> bool {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+            for path in paths.iter() {
+                if ext.match_def_path(callee_did, path) {
+                    for arg in callee_args.iter() {
+                        if_chain! {
+                            if let Operand::Move(place) = arg;
+                            let place_ty = place.ty(self.body, tcx);
+                            if let TyKind::RawPtr(ty_and_mut) = place_ty.ty.kind();
+                            let pointed_ty = ty_and_mut.ty;
+                            if pointed_ty.is_copy_modulo_regions(tcx.at(DUMMY_SP), self.param_env);
+                            then {
+                                return true;
+                            }
+                        }
+                        // No need to inspect beyond first arg of the
+                        // target bypass functions.
+                        break;
+                    }
+                }
+            }
+            false
+        }
+    }
+
+    fn trace_calls_in_body<'tcx>(rcx: RudraCtxt<'tcx>, body_def_id: DefId) {
+        warn!("Paths discovery function has been detected");
+        if let Ok(body) = rcx.translate_body(body_def_id).as_ref() {
+            for terminator in body.terminators() {
+                match terminator.kind {
+                    ir::TerminatorKind::StaticCall { callee_did, .. } => {
+                        let ext = rcx.tcx().ext();
+                        println!(
+                            "{}",
+                            ext.get_def_path(callee_did)
+                                .iter()
+                                .fold(String::new(), |a, b| a + " :: " + &*b.as_str())
+                        );
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+// Unsafe Dataflow BypassKind.
+// Used to associate each Unsafe-Dataflow bug report with its cause.
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u16 {
+        const READ_FLOW = 0b00000001;
+        const COPY_FLOW = 0b00000010;
+        const VEC_FROM_RAW = 0b00000100;
+        const TRANSMUTE = 0b00001000;
+        const WRITE_FLOW = 0b00010000;
+        const PTR_AS_REF = 0b00100000;
+        const SLICE_UNCHECKED = 0b01000000;
+        const SLICE_FROM_RAW = 0b10000000;
+        const VEC_SET_LEN = 0b100000000;
+        const USER_DEFINED = 0b1000000000;
+        const UNINIT = 0b10000000000;
+    }
+}
+
+impl BehaviorFlag {
+    /// Parses the name of a flag constant (e.g., `READ_FLOW`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "READ_FLOW" => BehaviorFlag::READ_FLOW,
+            "COPY_FLOW" => BehaviorFlag::COPY_FLOW,
+            "VEC_FROM_RAW" => BehaviorFlag::VEC_FROM_RAW,
+            "TRANSMUTE" => BehaviorFlag::TRANSMUTE,
+            "WRITE_FLOW" => BehaviorFlag::WRITE_FLOW,
+            "PTR_AS_REF" => BehaviorFlag::PTR_AS_REF,
+            "SLICE_UNCHECKED" => BehaviorFlag::SLICE_UNCHECKED,
+            "SLICE_FROM_RAW" => BehaviorFlag::SLICE_FROM_RAW,
+            "VEC_SET_LEN" => BehaviorFlag::VEC_SET_LEN,
+            "UNINIT" => BehaviorFlag::UNINIT,
+            _ => return None,
+        })
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        use BehaviorFlag as Flag;
+
+        let high = Flag::VEC_FROM_RAW | Flag::VEC_SET_LEN | Flag::UNINIT;
+        let med = Flag::READ_FLOW | Flag::COPY_FLOW | Flag::WRITE_FLOW | Flag::USER_DEFINED;
+
+        if !(*self & high).is_empty() {
+            ReportLevel::Error
+        } else if !(*self & med).is_empty() {
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Info
+        }
+    }
+}
+
+impl GraphTaint for BehaviorFlag {
+    fn is_empty(&self) -> bool {
+        self.is_all()
+    }
+
+    fn contains(&self, taint: &Self) -> bool {
+        self.contains(*taint)
+    }
+
+    fn join(&mut self, taint: &Self) {
+        *self |= *taint;
+    }
+}
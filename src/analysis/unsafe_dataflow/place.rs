@@ -0,0 +1,516 @@
+//! Place-sensitive taint tracking.
+//! Taint is attached to MIR locals instead of basic blocks,
+//! so that a sink is only considered dangerous when the bypassed value
+//! (or a value aliasing it) is still alive and owned at that point.
+
+use std::collections::VecDeque;
+
+use rustc_middle::mir::{
+    self, Field, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+};
+use rustc_middle::ty::{TyCtxt, TyKind};
+
+use super::*;
+use crate::graph::Graph;
+
+/// Bypasses that taint the memory behind their mutable pointer arguments.
+/// `UNINIT` comes with `VEC_SET_LEN` when the vector was never initialized.
+fn taints_pointee(flag: BehaviorFlag) -> BehaviorFlag {
+    flag & (BehaviorFlag::COPY_FLOW | BehaviorFlag::VEC_SET_LEN | BehaviorFlag::UNINIT)
+}
+
+/// Bypasses that taint the returned value.
+/// `ptr::write()` moves its argument into the pointee, so it does not taint anything.
+fn taints_return_value(flag: BehaviorFlag) -> BehaviorFlag {
+    flag - (BehaviorFlag::COPY_FLOW | BehaviorFlag::VEC_SET_LEN | BehaviorFlag::WRITE_FLOW)
+}
+
+/// Per-local taint state at the entry of a basic block (indexed by `Local`).
+type LocalTaint = Vec<BehaviorFlag>;
+
+/// Blocks from a sink to the cleanup block that drops a tainted place.
+pub struct UnwindPath {
+    pub blocks: Vec<usize>,
+    pub taint: BehaviorFlag,
+    /// The path ends by unwinding into the caller with tainted arguments
+    pub to_caller: bool,
+}
+
+pub struct PlaceTaintAnalyzer<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a ir::Body<'tcx>,
+    /// Local that each local was derived from (`_2 = &mut _1`, `_3 = move _2 as *mut T`, ...)
+    origins: Vec<Option<Local>>,
+    /// Taint introduced by the terminator of each block
+    sources: Vec<BehaviorFlag>,
+    sinks: Vec<bool>,
+    /// Calls that take ownership of their arguments without handing them back
+    /// (`mem::forget`, `ManuallyDrop::new`, `ptr::write`, ...)
+    consumers: Vec<bool>,
+}
+
+impl<'a, 'tcx> PlaceTaintAnalyzer<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+        let num_blocks = body.basic_blocks.len();
+        PlaceTaintAnalyzer {
+            tcx,
+            body,
+            origins: collect_origins(body),
+            sources: vec![BehaviorFlag::empty(); num_blocks],
+            sinks: vec![false; num_blocks],
+            consumers: vec![false; num_blocks],
+        }
+    }
+
+    pub fn mark_source(&mut self, id: usize, taint: &BehaviorFlag) {
+        self.sources[id] |= *taint;
+    }
+
+    pub fn mark_sink(&mut self, id: usize) {
+        self.sinks[id] = true;
+    }
+
+    pub fn mark_consumer(&mut self, id: usize) {
+        self.consumers[id] = true;
+    }
+
+    /// Returns the taint that is alive at any of the sinks.
+    pub fn propagate(&self) -> BehaviorFlag {
+        let entry_state = self.entry_states();
+
+        let mut ret = BehaviorFlag::empty();
+        for (id, entry) in entry_state.iter().enumerate() {
+            if let (true, Some(entry)) = (self.sinks[id], entry) {
+                let state = self.state_before_terminator(entry, id);
+                // The return place is not dropped during unwinding
+                for (local, taint) in state.iter().enumerate() {
+                    if local != mir::RETURN_PLACE.index() {
+                        ret |= *taint;
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Follows the unwind edge of each sink and returns the taint of the places
+    /// that are dropped on the cleanup path (or handed back to the caller while unwinding).
+    pub fn propagate_unwind(&self) -> (BehaviorFlag, Vec<UnwindPath>) {
+        let entry_state = self.entry_states();
+
+        let mut ret = BehaviorFlag::empty();
+        let mut paths = Vec::new();
+        for (id, entry) in entry_state.iter().enumerate() {
+            if let (true, Some(entry)) = (self.sinks[id], entry) {
+                let state = self.state_before_terminator(entry, id);
+                if let Some(path) = self.unwind_path(id, state) {
+                    ret |= path.taint;
+                    paths.push(path);
+                }
+            }
+        }
+
+        (ret, paths)
+    }
+
+    /// Computes the taint state at the entry of each reachable block.
+    fn entry_states(&self) -> Vec<Option<LocalTaint>> {
+        let num_locals = self.body.local_decls.len();
+        let mut entry_state: Vec<Option<LocalTaint>> = vec![None; self.body.basic_blocks.len()];
+        let mut work_list = VecDeque::new();
+
+        entry_state[0] = Some(vec![BehaviorFlag::empty(); num_locals]);
+        work_list.push_back(0);
+
+        while let Some(current) = work_list.pop_front() {
+            let mut state =
+                self.state_before_terminator(entry_state[current].as_ref().unwrap(), current);
+
+            let mut unwind_state = state.clone();
+            let unwind = self.apply_unwind(&mut unwind_state, current);
+            self.apply_terminator(&mut state, current);
+
+            for next in self.body.next(current) {
+                let next_taint = if Some(next) == unwind {
+                    // Only user-provided code (sinks) is assumed to panic
+                    if !self.sinks[current] {
+                        continue;
+                    }
+                    &unwind_state
+                } else {
+                    &state
+                };
+                let changed = match &mut entry_state[next] {
+                    Some(next_state) => join(next_state, next_taint),
+                    next_state @ None => {
+                        *next_state = Some(next_taint.clone());
+                        true
+                    }
+                };
+                if changed {
+                    work_list.push_back(next);
+                }
+            }
+        }
+
+        entry_state
+    }
+
+    fn state_before_terminator(&self, entry: &[BehaviorFlag], id: usize) -> LocalTaint {
+        let mut state = entry.to_vec();
+        for statement in self.body.basic_blocks[id].statements.iter() {
+            self.apply_statement(&mut state, statement);
+        }
+        state
+    }
+
+    /// Searches the cleanup blocks reachable from the unwind edge of `sink`
+    /// for a drop of a tainted place.
+    fn unwind_path(&self, sink: usize, mut state: LocalTaint) -> Option<UnwindPath> {
+        let unwind = match self.apply_unwind(&mut state, sink) {
+            Some(unwind) => unwind,
+            // Nothing to clean up, the panic directly unwinds into the caller
+            None => return self.unwind_to_caller(vec![sink], &state),
+        };
+
+        let mut visited = vec![false; self.body.basic_blocks.len()];
+        let mut work_list = vec![(unwind, state, vec![sink])];
+        while let Some((current, state, mut blocks)) = work_list.pop() {
+            if visited[current] {
+                continue;
+            }
+            visited[current] = true;
+            blocks.push(current);
+
+            let mut state = self.state_before_terminator(&state, current);
+            let terminator = &self.body.basic_blocks[current].terminator;
+            match &terminator.kind {
+                ir::TerminatorKind::Drop { place, .. } => {
+                    let taint = state[place.local.index()];
+                    if !taint.is_empty() {
+                        return Some(UnwindPath {
+                            blocks,
+                            taint,
+                            to_caller: false,
+                        });
+                    }
+                }
+                _ => {
+                    if let mir::TerminatorKind::Resume = terminator.original.kind {
+                        if let Some(path) = self.unwind_to_caller(blocks.clone(), &state) {
+                            return Some(path);
+                        }
+                    }
+                }
+            }
+
+            self.apply_terminator(&mut state, current);
+            for next in self.body.next(current) {
+                work_list.push((next, state.clone(), blocks.clone()));
+            }
+        }
+
+        None
+    }
+
+    fn apply_statement(&self, state: &mut LocalTaint, statement: &mir::Statement<'tcx>) {
+        match &statement.kind {
+            StatementKind::Assign(box (lhs, rvalue)) => {
+                let taint = self.rvalue_taint(state, rvalue);
+                if lhs.projection.is_empty() {
+                    state[lhs.local.index()] = taint;
+                } else {
+                    // Storing into a field or through a pointer keeps the previous taint
+                    state[lhs.local.index()] |= taint;
+                }
+            }
+            StatementKind::StorageDead(local) => {
+                state[local.index()] = BehaviorFlag::empty();
+            }
+            _ => (),
+        }
+    }
+
+    fn apply_terminator(&self, state: &mut LocalTaint, id: usize) {
+        let terminator = &self.body.basic_blocks[id].terminator;
+        match &terminator.kind {
+            ir::TerminatorKind::StaticCall {
+                args, destination, ..
+            }
+            | ir::TerminatorKind::FnPtr {
+                args, destination, ..
+            } => {
+                let mut taint = BehaviorFlag::empty();
+                for arg in args.iter() {
+                    taint |= self.operand_taint(state, arg);
+                }
+
+                if let Some((place, _)) = destination {
+                    let dest = &mut state[place.local.index()];
+                    if self.consumers[id] {
+                        *dest = BehaviorFlag::empty();
+                    } else if place.projection.is_empty() {
+                        *dest = taint;
+                    } else {
+                        *dest |= taint;
+                    }
+                }
+
+                let source = self.sources[id];
+                let pointee_taint = taints_pointee(source);
+                if !pointee_taint.is_empty() {
+                    for arg in args.iter() {
+                        if let Some(local) = self.mutable_pointer_arg(arg) {
+                            state[local.index()] |= pointee_taint;
+                            if let Some(root) = self.root(local) {
+                                state[root.index()] |= pointee_taint;
+                            }
+                        }
+                    }
+                }
+
+                let return_value_taint = taints_return_value(source);
+                if let (false, Some((place, _))) = (return_value_taint.is_empty(), destination) {
+                    state[place.local.index()] |= return_value_taint;
+                }
+            }
+            ir::TerminatorKind::Drop { place, replace, .. } => {
+                let taint = match replace {
+                    Some(value) => self.operand_taint(state, value),
+                    None => BehaviorFlag::empty(),
+                };
+                if place.projection.is_empty() {
+                    state[place.local.index()] = taint;
+                } else {
+                    state[place.local.index()] |= taint;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The caller drops the memory behind tainted arguments while unwinding.
+    fn unwind_to_caller(&self, blocks: Vec<usize>, state: &[BehaviorFlag]) -> Option<UnwindPath> {
+        let mut taint = BehaviorFlag::empty();
+        for arg_taint in state.iter().skip(1).take(self.body.original.arg_count) {
+            taint |= *arg_taint;
+        }
+        if taint.is_empty() {
+            None
+        } else {
+            Some(UnwindPath {
+                blocks,
+                taint,
+                to_caller: true,
+            })
+        }
+    }
+
+    /// Applies the terminator as seen from its unwind edge and returns the unwind target.
+    /// Arguments are moved and dropped places are gone, but the call did not return.
+    fn apply_unwind(&self, state: &mut LocalTaint, id: usize) -> Option<usize> {
+        match &self.body.basic_blocks[id].terminator.kind {
+            ir::TerminatorKind::StaticCall { args, cleanup, .. }
+            | ir::TerminatorKind::FnPtr { args, cleanup, .. } => {
+                for arg in args.iter() {
+                    self.operand_taint(state, arg);
+                }
+                *cleanup
+            }
+            ir::TerminatorKind::Drop { place, unwind, .. } => {
+                if place.projection.is_empty() {
+                    state[place.local.index()] = BehaviorFlag::empty();
+                }
+                *unwind
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the taint of an operand. Moving out of a local transfers its taint.
+    fn operand_taint(&self, state: &mut LocalTaint, operand: &Operand<'tcx>) -> BehaviorFlag {
+        match operand {
+            Operand::Copy(place) => state[place.local.index()],
+            Operand::Move(place) => {
+                let taint = state[place.local.index()];
+                if place.projection.is_empty() {
+                    state[place.local.index()] = BehaviorFlag::empty();
+                }
+                taint
+            }
+            Operand::Constant(_) => BehaviorFlag::empty(),
+        }
+    }
+
+    fn rvalue_taint(&self, state: &mut LocalTaint, rvalue: &Rvalue<'tcx>) -> BehaviorFlag {
+        match rvalue {
+            Rvalue::Use(operand) | Rvalue::Repeat(operand, _) | Rvalue::Cast(_, operand, _) => {
+                self.operand_taint(state, operand)
+            }
+            Rvalue::Aggregate(_, operands) => {
+                let mut taint = BehaviorFlag::empty();
+                for operand in operands.iter() {
+                    taint |= self.operand_taint(state, operand);
+                }
+                taint
+            }
+            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => state[place.local.index()],
+            // Arithmetic, length, discriminant, etc. produce plain data
+            _ => BehaviorFlag::empty(),
+        }
+    }
+
+    /// Returns the local of an argument of type `&mut T` or `*mut T`.
+    fn mutable_pointer_arg(&self, operand: &Operand<'tcx>) -> Option<Local> {
+        let place = operand.place()?;
+        match place.ty(self.body, self.tcx).ty.kind() {
+            TyKind::Ref(_, _, mir::Mutability::Mut) | TyKind::RawPtr(_) => Some(place.local),
+            _ => None,
+        }
+    }
+
+    fn root(&self, local: Local) -> Option<Local> {
+        find_root(&self.origins, local)
+    }
+}
+
+/// Follows `origins` to the local that `local` was ultimately derived from.
+pub(super) fn find_root(origins: &[Option<Local>], local: Local) -> Option<Local> {
+    let origin = origins[local.index()]?;
+    let chain = origin_chain(origins, origin, |origin| origin);
+    Some(chain.last().copied().unwrap_or(origin))
+}
+
+/// Follows `derived_from` (see `collect_origins` and `collect_derived_places`) from `local`
+/// and returns the origins on the way, nearest first. `local_of` gives the local of an origin.
+pub fn origin_chain<T: Copy>(
+    derived_from: &[Option<T>],
+    local: Local,
+    local_of: impl Fn(T) -> Local,
+) -> Vec<T> {
+    let mut chain = Vec::new();
+    let mut current = local;
+    // Bounded by the number of locals to avoid cycles in loops
+    for _ in 0..derived_from.len() {
+        match derived_from[current.index()] {
+            Some(origin) if local_of(origin) != current => {
+                chain.push(origin);
+                current = local_of(origin);
+            }
+            _ => break,
+        }
+    }
+    chain
+}
+
+/// The memory that a place is derived from: a local that is not derived from anything else,
+/// and the fields selected on the way (`&mut (*self).len` is `self` with field `len`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BasePlace {
+    pub local: Local,
+    pub fields: Vec<Field>,
+    /// False if a projection other than fields and derefs (e.g., indexing) ended the field list,
+    /// in which case the place covers the whole indexed value.
+    pub precise: bool,
+}
+
+impl BasePlace {
+    /// Follows `derived_from` (see `collect_derived_places`) and collects the field projections on the way.
+    pub fn new<'tcx>(derived_from: &[Option<Place<'tcx>>], place: Place<'tcx>) -> Self {
+        let mut chain = vec![place];
+        chain.extend(origin_chain(derived_from, place.local, |origin| {
+            origin.local
+        }));
+        let current = chain.last().unwrap().local;
+
+        let mut fields = Vec::new();
+        let mut precise = true;
+        'outer: for place in chain.iter().rev() {
+            for elem in place.projection.iter() {
+                match elem {
+                    ProjectionElem::Field(field, _) => fields.push(field),
+                    ProjectionElem::Deref => (),
+                    _ => {
+                        precise = false;
+                        break 'outer;
+                    }
+                }
+            }
+        }
+
+        BasePlace {
+            local: current,
+            fields,
+            precise,
+        }
+    }
+
+    /// Two places overlap if one of them contains the other
+    pub fn overlaps(&self, other: &BasePlace) -> bool {
+        self.local == other.local
+            && self
+                .fields
+                .iter()
+                .zip(other.fields.iter())
+                .all(|(lhs, rhs)| lhs == rhs)
+    }
+}
+
+/// Joins `taint` into `state`, returns true if `state` was changed.
+fn join(state: &mut LocalTaint, taint: &[BehaviorFlag]) -> bool {
+    let mut changed = false;
+    for (lhs, rhs) in state.iter_mut().zip(taint.iter()) {
+        if !lhs.contains(*rhs) {
+            *lhs |= *rhs;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// For each local, find the local that it is derived from by reference, cast or call.
+pub fn collect_origins(body: &ir::Body<'_>) -> Vec<Option<Local>> {
+    collect_derived_places(body)
+        .into_iter()
+        .map(|origin| origin.map(|place| place.local))
+        .collect()
+}
+
+/// For each local, finds the place that it is derived from by reference, cast or call.
+pub(super) fn collect_derived_places<'tcx>(body: &ir::Body<'tcx>) -> Vec<Option<Place<'tcx>>> {
+    let mut derived_from = vec![None; body.local_decls.len()];
+    for block in body.basic_blocks.iter() {
+        for statement in block.statements.iter() {
+            if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                let origin = match rvalue {
+                    Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => Some(*place),
+                    Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => operand.place(),
+                    _ => None,
+                };
+                if let (true, Some(origin)) = (lhs.projection.is_empty(), origin) {
+                    derived_from[lhs.local.index()].get_or_insert(origin);
+                }
+            }
+        }
+
+        // Pointers returned from methods (`as_mut_ptr()`, `add()`, ...) point into their receiver
+        if let ir::TerminatorKind::StaticCall {
+            args,
+            destination: Some((place, _)),
+            ..
+        } = &block.terminator.kind
+        {
+            if let Some(receiver) = args.get(0).and_then(|arg| arg.place()) {
+                let receiver_ty = body.local_decls[receiver.local.index()].ty;
+                if place.projection.is_empty()
+                    && receiver.projection.is_empty()
+                    && matches!(receiver_ty.kind(), TyKind::Ref(..) | TyKind::RawPtr(_))
+                {
+                    derived_from[place.local.index()].get_or_insert(receiver);
+                }
+            }
+        }
+    }
+    derived_from
+}
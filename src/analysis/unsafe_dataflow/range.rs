@@ -0,0 +1,281 @@
+//! Symbolic upper bounds of `usize` locals, used to tell shrinking `Vec::set_len()` calls
+//! (`set_len(len - 1)`, `set_len(old_len)`) from the ones that extend a vector.
+
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_middle::mir::{
+    BasicBlock, BinOp, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+};
+use rustc_middle::ty::ParamEnv;
+
+use super::place::{collect_derived_places, BasePlace};
+use super::*;
+
+/// The quantity a bound is relative to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundBase {
+    /// An absolute value
+    Zero,
+    /// `len()` of a vector or a slice (e.g., `self.buf`), observed at the end of `block`
+    Len { place: BasePlace, block: usize },
+}
+
+/// An upper bound `base + offset` of a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpperBound {
+    Bounded { base: BoundBase, offset: i128 },
+    Unknown,
+}
+
+impl UpperBound {
+    fn constant(value: u128) -> Self {
+        UpperBound::Bounded {
+            base: BoundBase::Zero,
+            offset: value as i128,
+        }
+    }
+
+    fn shift(self, delta: i128) -> Self {
+        match self {
+            UpperBound::Bounded { base, offset } => UpperBound::Bounded {
+                base,
+                offset: offset.saturating_add(delta),
+            },
+            UpperBound::Unknown => UpperBound::Unknown,
+        }
+    }
+
+    /// The least bound that holds for both values
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (
+                UpperBound::Bounded { base, offset },
+                UpperBound::Bounded {
+                    base: other_base,
+                    offset: other_offset,
+                },
+            ) if base == other_base => UpperBound::Bounded {
+                base,
+                offset: offset.max(other_offset),
+            },
+            _ => UpperBound::Unknown,
+        }
+    }
+
+    /// Returns true if the value never exceeds the length of `container`
+    /// observed on every path to `block`.
+    fn within_len_of(
+        &self,
+        container: &BasePlace,
+        block: usize,
+        dominators: &Dominators<BasicBlock>,
+    ) -> bool {
+        match self {
+            UpperBound::Bounded {
+                base: BoundBase::Zero,
+                offset,
+            } => *offset <= 0,
+            UpperBound::Bounded {
+                base:
+                    BoundBase::Len {
+                        place,
+                        block: len_block,
+                    },
+                offset,
+            } => {
+                place.precise
+                    && place == container
+                    && *offset <= 0
+                    && dominators.is_dominated_by(
+                        BasicBlock::from_usize(block),
+                        BasicBlock::from_usize(*len_block),
+                    )
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct RangeAnalyzer<'a, 'tcx> {
+    rcx: RudraCtxt<'tcx>,
+    body: &'a ir::Body<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    derived_from: Vec<Option<Place<'tcx>>>,
+    dominators: Dominators<BasicBlock>,
+    /// `None` if the local is never assigned
+    bounds: Vec<Option<UpperBound>>,
+}
+
+impl<'a, 'tcx> RangeAnalyzer<'a, 'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>, param_env: ParamEnv<'tcx>) -> Self {
+        let mut analyzer = RangeAnalyzer {
+            rcx,
+            body,
+            param_env,
+            derived_from: collect_derived_places(body),
+            dominators: body.original.dominators(),
+            bounds: vec![None; body.local_decls.len()],
+        };
+        analyzer.compute();
+        analyzer
+    }
+
+    /// Returns true if `Vec::set_len()` in block `id` never makes the vector longer than
+    /// the length observed before the call (e.g., `set_len(0)`, `set_len(len - 1)`).
+    pub fn is_non_growing_set_len(&self, id: usize, args: &[Operand<'tcx>]) -> bool {
+        match (args.get(0).and_then(|arg| self.base_of(arg)), args.get(1)) {
+            (Some(vec), Some(new_len)) => {
+                self.bound_of(new_len)
+                    .within_len_of(&vec, id, &self.dominators)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if `from_raw_parts(ptr, len)` in block `id` creates a slice within
+    /// the length of the container that `ptr` points into.
+    pub fn is_in_bounds_from_raw_parts(&self, id: usize, args: &[Operand<'tcx>]) -> bool {
+        match (args.get(0).and_then(|arg| self.base_of(arg)), args.get(1)) {
+            (Some(container), Some(len)) => {
+                self.bound_of(len)
+                    .within_len_of(&container, id, &self.dominators)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn bound_of(&self, operand: &Operand<'tcx>) -> UpperBound {
+        match operand {
+            Operand::Constant(constant) => {
+                let tcx = self.rcx.tcx();
+                if constant.ty() != tcx.types.usize {
+                    return UpperBound::Unknown;
+                }
+                match constant.literal.try_eval_usize(tcx, self.param_env) {
+                    Some(value) => UpperBound::constant(value.into()),
+                    None => UpperBound::Unknown,
+                }
+            }
+            Operand::Copy(place) | Operand::Move(place) => {
+                // `.0` of a checked arithmetic result is the value itself
+                let is_value = match place.projection.as_ref() {
+                    [] => true,
+                    [ProjectionElem::Field(field, _)] => field.index() == 0,
+                    _ => false,
+                };
+                match (is_value, &self.bounds[place.local.index()]) {
+                    (true, Some(bound)) => bound.clone(),
+                    _ => UpperBound::Unknown,
+                }
+            }
+        }
+    }
+
+    /// Finds the container (local and fields) that an operand is derived from.
+    fn base_of(&self, operand: &Operand<'tcx>) -> Option<BasePlace> {
+        operand
+            .place()
+            .map(|place| BasePlace::new(&self.derived_from, place))
+    }
+
+    fn assign(&mut self, local: Local, bound: UpperBound) -> bool {
+        let slot = &mut self.bounds[local.index()];
+        let joined = match slot.clone() {
+            // A bound that grows again (`i = i + 1` in a loop) is widened to guarantee termination
+            Some(prev) if prev != bound => match prev.clone().join(bound) {
+                joined if joined == prev => prev,
+                _ => UpperBound::Unknown,
+            },
+            Some(prev) => prev,
+            None => bound,
+        };
+        let changed = slot.as_ref() != Some(&joined);
+        *slot = Some(joined);
+        changed
+    }
+
+    fn rvalue_bound(&self, rvalue: &Rvalue<'tcx>) -> UpperBound {
+        match rvalue {
+            Rvalue::Use(operand) => self.bound_of(operand),
+            Rvalue::BinaryOp(bin_op, box (lhs, rhs))
+            | Rvalue::CheckedBinaryOp(bin_op, box (lhs, rhs)) => {
+                let shift = match self.bound_of(rhs) {
+                    UpperBound::Bounded {
+                        base: BoundBase::Zero,
+                        offset,
+                    } => offset,
+                    UpperBound::Unknown | UpperBound::Bounded { .. } => {
+                        return UpperBound::Unknown;
+                    }
+                };
+                match bin_op {
+                    BinOp::Add => self.bound_of(lhs).shift(shift),
+                    BinOp::Sub => self.bound_of(lhs).shift(-shift),
+                    _ => UpperBound::Unknown,
+                }
+            }
+            _ => UpperBound::Unknown,
+        }
+    }
+
+    fn call_bound(&self, block: usize, callee_did: DefId, args: &[Operand<'tcx>]) -> UpperBound {
+        let ext = self.rcx.tcx().ext();
+        if ext.match_def_path(callee_did, &VEC_LEN) || ext.match_def_path(callee_did, &SLICE_LEN) {
+            if let Some(place) = args.get(0).and_then(|arg| self.base_of(arg)) {
+                return UpperBound::Bounded {
+                    base: BoundBase::Len { place, block },
+                    offset: 0,
+                };
+            }
+        } else if ext.match_def_path(callee_did, &CMP_MIN)
+            || ext.match_def_path(callee_did, &ORD_MIN)
+        {
+            // The result is bounded by either of the arguments
+            return args
+                .iter()
+                .map(|arg| self.bound_of(arg))
+                .find(|bound| matches!(bound, UpperBound::Bounded { .. }))
+                .unwrap_or(UpperBound::Unknown);
+        }
+        UpperBound::Unknown
+    }
+
+    /// Computes the bounds of all locals, ignoring the control flow.
+    fn compute(&mut self) {
+        for arg_idx in 1..=self.body.original.arg_count {
+            self.bounds[arg_idx] = Some(UpperBound::Unknown);
+        }
+
+        let body = self.body;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, block) in body.basic_blocks.iter().enumerate() {
+                for statement in block.statements.iter() {
+                    if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                        let bound = if lhs.projection.is_empty() {
+                            self.rvalue_bound(rvalue)
+                        } else {
+                            UpperBound::Unknown
+                        };
+                        changed |= self.assign(lhs.local, bound);
+                    }
+                }
+
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    ref args,
+                    destination: Some((place, _)),
+                    ..
+                } = block.terminator.kind
+                {
+                    let bound = if place.projection.is_empty() {
+                        self.call_bound(id, callee_did, args)
+                    } else {
+                        UpperBound::Unknown
+                    };
+                    changed |= self.assign(place.local, bound);
+                }
+            }
+        }
+    }
+}
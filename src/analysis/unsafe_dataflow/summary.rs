@@ -0,0 +1,77 @@
+//! Per-function summaries for interprocedural unsafe dataflow analysis
+
+use std::cell::RefCell;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+
+use super::*;
+
+/// Behavior of a local function that is observable from its callers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FnSummary {
+    /// Strong lifetime bypasses that may happen inside the function (transitively).
+    pub strong_bypass: BehaviorFlag,
+    /// Weak lifetime bypasses that may happen inside the function (transitively).
+    pub weak_bypass: BehaviorFlag,
+    /// Whether the function may call generic code that it cannot resolve.
+    pub calls_unresolvable_generic: bool,
+    /// Whether the function may call trait objects or function pointers.
+    pub calls_dynamic: bool,
+}
+
+impl FnSummary {
+    pub fn bypass_flag(&self) -> BehaviorFlag {
+        self.strong_bypass | self.weak_bypass
+    }
+}
+
+/// Computes `FnSummary` on demand and caches the result per `DefId`.
+pub struct SummaryCache<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+    mode: UnsafeDataflowMode,
+    cache: RefCell<FxHashMap<DefId, FnSummary>>,
+}
+
+impl<'tcx> SummaryCache<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>, mode: UnsafeDataflowMode) -> Self {
+        SummaryCache {
+            rcx,
+            mode,
+            cache: RefCell::new(FxHashMap::default()),
+        }
+    }
+
+    /// Analysis mode used for both summaries and their callers.
+    pub fn mode(&self) -> UnsafeDataflowMode {
+        self.mode
+    }
+
+    /// Returns the summary of a local function.
+    /// Functions without MIR (and recursive calls that are still being summarized)
+    /// get an empty summary.
+    pub fn summary(&self, def_id: DefId) -> FnSummary {
+        if let Some(summary) = self.cache.borrow().get(&def_id) {
+            return *summary;
+        }
+
+        // Insert a placeholder first to cut off recursion
+        self.cache.borrow_mut().insert(def_id, FnSummary::default());
+
+        let summary = match self.rcx.translate_body(def_id).as_ref() {
+            Err(e) => {
+                e.log();
+                FnSummary::default()
+            }
+            Ok(body) => {
+                let param_env = self.rcx.tcx().param_env(def_id);
+                inner::UnsafeDataflowBodyAnalyzer::new(self.rcx, self, param_env, body)
+                    .analyze()
+                    .summary()
+            }
+        };
+
+        self.cache.borrow_mut().insert(def_id, summary);
+        summary
+    }
+}
@@ -0,0 +1,194 @@
+//! Buffers that are exposed without being initialized
+//! (`Vec::with_capacity()` + `set_len()`, `mem::uninitialized()`, ...)
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::{self, Local, Operand, Rvalue, StatementKind};
+
+use super::place::{collect_origins, find_root};
+use super::*;
+use crate::graph::Graph;
+
+/// A buffer that becomes uninitialized memory at the end of a block
+pub struct UninitBuffer {
+    /// The local that owns the buffer
+    pub root: Local,
+    /// The block whose terminator creates the uninitialized buffer
+    pub block: usize,
+    /// The function that creates the uninitialized buffer
+    pub origin: DefId,
+}
+
+pub struct UninitBufferCollector<'a, 'tcx> {
+    rcx: RudraCtxt<'tcx>,
+    body: &'a ir::Body<'tcx>,
+    origins: Vec<Option<Local>>,
+}
+
+impl<'a, 'tcx> UninitBufferCollector<'a, 'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>, body: &'a ir::Body<'tcx>) -> Self {
+        UninitBufferCollector {
+            rcx,
+            body,
+            origins: collect_origins(body),
+        }
+    }
+
+    /// Finds the local that an operand is derived from.
+    pub fn root_of(&self, operand: &Operand<'tcx>) -> Option<Local> {
+        operand
+            .place()
+            .map(|place| find_root(&self.origins, place.local).unwrap_or(place.local))
+    }
+
+    pub fn collect(&self) -> Vec<UninitBuffer> {
+        let ext = self.rcx.tcx().ext();
+        let mut buffers = Vec::new();
+        for (id, terminator) in self.body.terminators().enumerate() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                args,
+                destination,
+                ..
+            } = &terminator.kind
+            {
+                if ext.match_def_path(*callee_did, &VEC_SET_LEN) {
+                    if let (true, Some(root)) = (
+                        self.is_uninit_vec_set_len(id, args),
+                        args.get(0).and_then(|arg| self.root_of(arg)),
+                    ) {
+                        buffers.push(UninitBuffer {
+                            root,
+                            block: id,
+                            origin: *callee_did,
+                        });
+                    }
+                } else if ext.match_def_path(*callee_did, &MEM_UNINITIALIZED)
+                    || (ext.match_def_path(*callee_did, &MAYBE_UNINIT_ASSUME_INIT)
+                        && self.is_uninit_assume_init(args))
+                {
+                    // `mem::zeroed()` is excluded, since zeroed bytes are initialized
+                    if let Some((place, _)) = destination {
+                        buffers.push(UninitBuffer {
+                            root: find_root(&self.origins, place.local).unwrap_or(place.local),
+                            block: id,
+                            origin: *callee_did,
+                        });
+                    }
+                }
+            }
+        }
+        buffers
+    }
+
+    /// Returns true if the receiver of `MaybeUninit::assume_init()` comes straight from
+    /// `MaybeUninit::uninit()` or `MaybeUninit::uninit_array()`, without being borrowed
+    /// (and possibly written through `write()` or `as_mut_ptr()`) in between.
+    pub fn is_uninit_assume_init(&self, args: &[Operand<'tcx>]) -> bool {
+        let mut local = match args.get(0).and_then(|arg| arg.place()) {
+            Some(place) if place.projection.is_empty() => place.local,
+            _ => return false,
+        };
+
+        let ext = self.rcx.tcx().ext();
+        // Bounded by the number of locals to avoid cycles in loops
+        for _ in 0..self.body.local_decls.len() {
+            if self.is_borrowed_mut(local) {
+                return false;
+            }
+
+            let mut moved_from = None;
+            for block in self.body.basic_blocks.iter() {
+                for statement in block.statements.iter() {
+                    if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                        if lhs.local != local {
+                            continue;
+                        }
+                        match rvalue {
+                            Rvalue::Use(Operand::Move(place) | Operand::Copy(place))
+                                if lhs.projection.is_empty() && place.projection.is_empty() =>
+                            {
+                                moved_from = Some(place.local)
+                            }
+                            _ => return false,
+                        }
+                    }
+                }
+
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    destination: Some((place, _)),
+                    ..
+                } = &block.terminator.kind
+                {
+                    if place.local == local {
+                        return place.projection.is_empty()
+                            && (ext.match_def_path(*callee_did, &MAYBE_UNINIT_UNINIT)
+                                || ext.match_def_path(*callee_did, &MAYBE_UNINIT_UNINIT_ARRAY));
+                    }
+                }
+            }
+
+            match moved_from {
+                Some(source) => local = source,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    fn is_borrowed_mut(&self, local: Local) -> bool {
+        self.body.basic_blocks.iter().any(|block| {
+            block
+                .statements
+                .iter()
+                .any(|statement| match &statement.kind {
+                    StatementKind::Assign(box (_, rvalue)) => match rvalue {
+                        Rvalue::Ref(_, kind, place) => {
+                            place.local == local && *kind != mir::BorrowKind::Shared
+                        }
+                        Rvalue::AddressOf(mir::Mutability::Mut, place) => place.local == local,
+                        _ => false,
+                    },
+                    _ => false,
+                })
+        })
+    }
+
+    /// Returns true if `Vec::set_len()` in block `id` is called on a vector
+    /// created by `Vec::with_capacity()` that is not written to before the call.
+    pub fn is_uninit_vec_set_len(&self, id: usize, args: &[Operand<'tcx>]) -> bool {
+        let vec_root = match args.get(0).and_then(|arg| self.root_of(arg)) {
+            Some(root) => root,
+            None => return false,
+        };
+
+        let ext = self.rcx.tcx().ext();
+        let mut from_with_capacity = false;
+        for (call_id, terminator) in self.body.terminators().enumerate() {
+            if let ir::TerminatorKind::StaticCall {
+                callee_did,
+                args: call_args,
+                destination,
+                ..
+            } = &terminator.kind
+            {
+                if ext.match_def_path(*callee_did, &VEC_WITH_CAPACITY) {
+                    if let Some((place, _)) = destination {
+                        from_with_capacity |= place.local == vec_root;
+                    }
+                } else if call_id != id
+                    && !ext.match_def_path(*callee_did, &VEC_LEN)
+                    && !ext.match_def_path(*callee_did, &VEC_CAPACITY)
+                    && call_args
+                        .iter()
+                        .any(|arg| self.root_of(arg) == Some(vec_root))
+                    && self.body.reaches(call_id, id)
+                {
+                    // The buffer may have been initialized through this call
+                    return false;
+                }
+            }
+        }
+        from_with_capacity
+    }
+}
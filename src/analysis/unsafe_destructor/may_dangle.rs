@@ -0,0 +1,156 @@
+//! `#[may_dangle]` misuse detector
+//!
+//! `unsafe impl<#[may_dangle] T> Drop for Foo<T>` promises that `drop()` doesn't access values of `T`
+//! other than dropping them. Without trait bounds on `T`, generic code can only move and drop `T`,
+//! so an access always goes through a trait method of `T` (`<T as Display>::fmt`, `<T as Clone>::clone`, ...),
+//! or a function whose trait bounds on `T` allow it to call one (`<Vec<T> as Debug>::fmt`).
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::{Operand, Rvalue, StatementKind};
+use rustc_middle::ty::{
+    self, subst::GenericArgKind, subst::SubstsRef, Instance, ParamEnv, PredicateKind, TyCtxt,
+};
+use rustc_span::Span;
+
+use crate::ir;
+
+/// A call in `drop()` that may access a `#[may_dangle]` type parameter
+pub struct DanglingAccess {
+    pub span: Span,
+    /// Name of the `#[may_dangle]` type parameter
+    pub param: String,
+    /// Path of the function that accesses the parameter
+    pub callee: String,
+}
+
+/// Indices of the type parameters of the impl that are marked `#[may_dangle]`
+pub fn may_dangle_params(tcx: TyCtxt<'_>, impl_did: DefId) -> Vec<u32> {
+    tcx.generics_of(impl_did)
+        .params
+        .iter()
+        .filter(|param| {
+            param.pure_wrt_drop && matches!(param.kind, ty::GenericParamDefKind::Type { .. })
+        })
+        .map(|param| param.index)
+        .collect()
+}
+
+pub fn dangling_accesses<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &ir::Body<'tcx>,
+    dangling: &[u32],
+) -> Vec<DanglingAccess> {
+    let mut accesses = Vec::new();
+    if dangling.is_empty() {
+        return accesses;
+    }
+
+    let mut check = |span: Span, callee_did: DefId, callee_substs: SubstsRef<'tcx>| {
+        if let Some(param) = accessed_param(tcx, param_env, callee_did, callee_substs, dangling) {
+            accesses.push(DanglingAccess {
+                span,
+                param,
+                callee: tcx.def_path_str_with_substs(callee_did, callee_substs),
+            });
+        }
+    };
+
+    for block in body.basic_blocks.iter() {
+        // Functions that are not called directly (e.g., `<T as Display>::fmt` in `format!()`)
+        for statement in block.statements.iter() {
+            if let StatementKind::Assign(box (
+                _,
+                Rvalue::Use(operand) | Rvalue::Cast(_, operand, _),
+            )) = &statement.kind
+            {
+                if let Some((fn_did, fn_substs)) = fn_def_of(operand) {
+                    check(statement.source_info.span, fn_did, fn_substs);
+                }
+            }
+        }
+
+        let terminator = &block.terminator;
+        if let ir::TerminatorKind::StaticCall {
+            callee_did,
+            callee_substs,
+            ref args,
+            ..
+        } = terminator.kind
+        {
+            check(
+                terminator.original.source_info.span,
+                callee_did,
+                callee_substs,
+            );
+            for (fn_did, fn_substs) in args.iter().filter_map(fn_def_of) {
+                check(terminator.original.source_info.span, fn_did, fn_substs);
+            }
+        }
+    }
+    accesses
+}
+
+fn fn_def_of<'tcx>(operand: &Operand<'tcx>) -> Option<(DefId, SubstsRef<'tcx>)> {
+    match operand {
+        Operand::Constant(constant) => match constant.ty().kind() {
+            ty::TyKind::FnDef(fn_did, fn_substs) => Some((*fn_did, fn_substs)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the name of the `#[may_dangle]` parameter if the function may access it.
+fn accessed_param<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    callee_did: DefId,
+    callee_substs: SubstsRef<'tcx>,
+    dangling: &[u32],
+) -> Option<String> {
+    let dangling_param_in = |ty: ty::Ty<'tcx>| {
+        ty.walk(tcx).find_map(|arg| match arg.unpack() {
+            GenericArgKind::Type(ty) => match ty.kind() {
+                ty::TyKind::Param(param_ty) if dangling.contains(&param_ty.index) => {
+                    Some(param_ty.name.to_string())
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+    };
+    let param = callee_substs.types().find_map(dangling_param_in)?;
+
+    let instance = match Instance::resolve(tcx, param_env, callee_did, callee_substs) {
+        Ok(Some(instance)) => instance,
+        // A trait method of the parameter itself
+        Ok(None) => return Some(param),
+        Err(_) => return None,
+    };
+
+    // Trait bounds that let the function call methods of the parameter.
+    // Bounds on other types (`Drain<T>: Iterator`) only access `T` through their own impls.
+    let lang_items = tcx.lang_items();
+    let predicates = tcx
+        .predicates_of(instance.def_id())
+        .instantiate(tcx, instance.substs);
+    predicates
+        .predicates
+        .iter()
+        .any(|predicate| match predicate.kind().skip_binder() {
+            PredicateKind::Trait(trait_predicate) => {
+                let trait_did = trait_predicate.def_id();
+                Some(trait_did) != lang_items.sized_trait()
+                    && Some(trait_did) != lang_items.copy_trait()
+                    && !tcx.trait_is_auto(trait_did)
+                    && is_dangling_param(trait_predicate.self_ty().peel_refs(), dangling)
+            }
+            _ => false,
+        })
+        .then(|| param)
+}
+
+fn is_dangling_param(ty: ty::Ty<'_>, dangling: &[u32]) -> bool {
+    matches!(ty.kind(), ty::TyKind::Param(param_ty) if dangling.contains(&param_ty.index))
+}
@@ -0,0 +1,719 @@
+//! Unsafe destructor detector
+//!
+//! Analyzes the MIR of `Drop::drop()` for fields of `self` that are used or dropped again
+//! after being moved out (`ptr::read()`) or overwritten with uninitialized memory,
+//! and for generic code that is called after the memory owned by `self` is freed.
+//! Destructors that access `#[may_dangle]` type parameters are reported as well.
+mod may_dangle;
+mod self_ref;
+
+use std::collections::BTreeSet;
+
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::mir::{self, Local, Operand, Place, Rvalue, StatementKind};
+use rustc_middle::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
+use rustc_span::Span;
+
+use snafu::{Backtrace, OptionExt, Snafu};
+
+use crate::analysis::unsafe_dataflow::{collect_origins, has_unresolvable_drop, origin_chain};
+use crate::analysis::{AnalysisKind, IntoReportLevel};
+use crate::graph::Graph;
+use crate::iter::LocalTraitIter;
+use crate::prelude::*;
+use crate::report::{Report, ReportLevel, SpanRole};
+use crate::{ir, paths::*, utils};
+
+use may_dangle::{dangling_accesses, may_dangle_params};
+use self_ref::{SelfRef, SelfRefs};
+
+#[derive(Debug, Snafu)]
+pub enum UnsafeDestructorError {
+    DropTraitNotFound,
+    UnexpectedDropItem,
+    ResolveError { backtrace: Backtrace },
+    InvalidSpan { backtrace: Backtrace },
+}
+
+impl AnalysisError for UnsafeDestructorError {
+    fn kind(&self) -> AnalysisErrorKind {
+        use UnsafeDestructorError::*;
+        match self {
+            DropTraitNotFound => AnalysisErrorKind::Unreachable,
+            UnexpectedDropItem => AnalysisErrorKind::Unreachable,
+            ResolveError { .. } => AnalysisErrorKind::OutOfScope,
+            InvalidSpan { .. } => AnalysisErrorKind::Unreachable,
+        }
+    }
+}
+
+pub struct UnsafeDestructorChecker<'tcx> {
+    rcx: RudraCtxt<'tcx>,
+}
+
+impl<'tcx> UnsafeDestructorChecker<'tcx> {
+    pub fn new(rcx: RudraCtxt<'tcx>) -> Self {
+        UnsafeDestructorChecker { rcx }
+    }
+
+    pub fn analyze(&mut self) {
+        fn drop_trait_def_id<'tcx>(tcx: TyCtxt<'tcx>) -> AnalysisResult<'tcx, DefId> {
+            convert!(tcx.lang_items().drop_trait().context(DropTraitNotFound))
+        }
+
+        let tcx = self.rcx.tcx();
+        let drop_trait_def_id = unwrap_or!(drop_trait_def_id(tcx) => return);
+
+        for impl_id in LocalTraitIter::new(self.rcx, drop_trait_def_id) {
+            // `Drop` trait has only one required function.
+            let drop_fn_did = match tcx.associated_item_def_ids(impl_id) {
+                [drop_fn_did] => *drop_fn_did,
+                _ => {
+                    log_err!(UnexpectedDropItem);
+                    continue;
+                }
+            };
+            let body = match self.rcx.translate_body(drop_fn_did).as_ref() {
+                Err(e) => {
+                    // MIR is not available for def - log it and continue
+                    e.log();
+                    continue;
+                }
+                Ok(body) => {
+                    inner::DropBodyAnalyzer::new(self.rcx, impl_id, drop_fn_did, body).analyze()
+                }
+            };
+
+            let behavior_flag = body.behavior_flag();
+            if behavior_flag.is_empty()
+                || behavior_flag.report_level() < self.rcx.report_level()
+                || self.rcx.is_suppressed("unsafe_destructor", drop_fn_did)
+            {
+                continue;
+            }
+
+            let hir_map = tcx.hir();
+            let drop_fn_span =
+                hir_map.span_with_body(hir_map.local_def_id_to_hir_id(drop_fn_did.expect_local()));
+            let mut color_span = unwrap_or!(
+                utils::ColorSpan::new(tcx, drop_fn_span).context(InvalidSpan) => continue
+            );
+            for &span in body.invalidation_spans() {
+                color_span.add_sub_span(SpanRole::Invalidation, span);
+            }
+            for &span in body.use_spans() {
+                color_span.add_sub_span(SpanRole::Use, span);
+            }
+            for &span in body.generic_call_spans() {
+                color_span.add_sub_span(SpanRole::UnresolvableGenericCall, span);
+            }
+
+            rudra_report(Report::with_color_span(
+                tcx,
+                behavior_flag.report_level(),
+                AnalysisKind::UnsafeDestructor(behavior_flag),
+                format!(
+                    "Unsafe destructor of `{}`: {}",
+                    tcx.type_of(impl_id),
+                    body.details()
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                drop_fn_did,
+                &color_span,
+            ));
+        }
+    }
+}
+
+mod inner {
+    use super::*;
+
+    /// How a field of `self` became invalid
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Invalidation {
+        /// Moved out by `ptr::read()` into the local
+        Moved(Local),
+        /// Overwritten with uninitialized memory
+        Uninit,
+    }
+
+    /// What happens to an invalidated field afterwards
+    #[derive(Debug, Default)]
+    struct Aftermath {
+        uses: Vec<Span>,
+        /// The field is dropped explicitly, or implicitly after `drop()` returns (or unwinds)
+        dropped: bool,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct DropBodyStatus {
+        behavior_flag: BehaviorFlag,
+        invalidations: Vec<Span>,
+        uses: Vec<Span>,
+        generic_calls: Vec<Span>,
+        details: BTreeSet<String>,
+    }
+
+    impl DropBodyStatus {
+        pub fn behavior_flag(&self) -> BehaviorFlag {
+            self.behavior_flag
+        }
+
+        pub fn invalidation_spans(&self) -> &Vec<Span> {
+            &self.invalidations
+        }
+
+        pub fn use_spans(&self) -> &Vec<Span> {
+            &self.uses
+        }
+
+        pub fn generic_call_spans(&self) -> &Vec<Span> {
+            &self.generic_calls
+        }
+
+        pub fn details(&self) -> &BTreeSet<String> {
+            &self.details
+        }
+
+        fn add(&mut self, flag: BehaviorFlag, detail: String) {
+            self.behavior_flag |= flag;
+            self.details.insert(detail);
+        }
+    }
+
+    pub struct DropBodyAnalyzer<'a, 'tcx> {
+        rcx: RudraCtxt<'tcx>,
+        body: &'a ir::Body<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        self_ty: Ty<'tcx>,
+        self_refs: SelfRefs,
+        origins: Vec<Option<Local>>,
+        /// Locals that hold uninitialized memory (`mem::uninitialized()`, `MaybeUninit::assume_init()`)
+        uninit_locals: Vec<Local>,
+        /// Indices of the `#[may_dangle]` type parameters
+        dangling_params: Vec<u32>,
+        status: DropBodyStatus,
+    }
+
+    impl<'a, 'tcx> DropBodyAnalyzer<'a, 'tcx> {
+        pub fn new(
+            rcx: RudraCtxt<'tcx>,
+            impl_id: LocalDefId,
+            drop_fn_did: DefId,
+            body: &'a ir::Body<'tcx>,
+        ) -> Self {
+            let tcx = rcx.tcx();
+            DropBodyAnalyzer {
+                rcx,
+                body,
+                param_env: tcx.param_env(drop_fn_did),
+                self_ty: tcx.type_of(impl_id),
+                self_refs: SelfRefs::new(body),
+                origins: collect_origins(body),
+                uninit_locals: Vec::new(),
+                dangling_params: may_dangle_params(tcx, impl_id.to_def_id()),
+                status: Default::default(),
+            }
+        }
+
+        pub fn analyze(mut self) -> DropBodyStatus {
+            let tcx = self.rcx.tcx();
+            let ext = tcx.ext();
+
+            for terminator in self.body.terminators() {
+                if let ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    destination: Some((place, _)),
+                    ..
+                } = terminator.kind
+                {
+                    if ext.match_def_path(callee_did, &MEM_UNINITIALIZED)
+                        || ext.match_def_path(callee_did, &MAYBE_UNINIT_ASSUME_INIT)
+                    {
+                        self.uninit_locals.push(place.local);
+                    }
+                }
+            }
+
+            for (id, terminator) in self.body.terminators().enumerate() {
+                let span = terminator.original.source_info.span;
+                match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did,
+                        args,
+                        destination,
+                        ..
+                    } => {
+                        let callee_did = *callee_did;
+                        if ext.match_def_path(callee_did, &PTR_READ)
+                            || ext.match_def_path(callee_did, &PTR_DIRECT_READ)
+                        {
+                            // Reading `Copy` types (or types without drop glue) doesn't move them out
+                            if let (Some(SelfRef::Addr(path)), Some((place, _))) = (
+                                args.get(0).and_then(|arg| self.self_refs.of_operand(arg)),
+                                destination,
+                            ) {
+                                let read_ty = place.ty(self.body, tcx).ty;
+                                if !path.is_empty() && read_ty.needs_drop(tcx, self.param_env) {
+                                    self.check_invalidation(
+                                        id,
+                                        span,
+                                        &path,
+                                        read_ty,
+                                        Invalidation::Moved(place.local),
+                                    );
+                                }
+                            }
+                        } else if ext.match_def_path(callee_did, &PTR_WRITE)
+                            || ext.match_def_path(callee_did, &PTR_DIRECT_WRITE)
+                            || ext.match_def_path(callee_did, &MEM_REPLACE)
+                        {
+                            if let (Some(SelfRef::Addr(path)), Some(value)) = (
+                                args.get(0).and_then(|arg| self.self_refs.of_operand(arg)),
+                                args.get(1),
+                            ) {
+                                if !path.is_empty() && self.is_uninit(value) {
+                                    let value_ty = value.ty(self.body, tcx);
+                                    self.check_invalidation(
+                                        id,
+                                        span,
+                                        &path,
+                                        value_ty,
+                                        Invalidation::Uninit,
+                                    );
+                                }
+                            }
+                        } else if FREE_FN_LIST.contains(&ext.get_def_path(callee_did)) {
+                            if let Some(self_ref) = args
+                                .iter()
+                                .filter_map(|arg| self.self_refs.of_operand(arg))
+                                .find(|self_ref| !self_ref.path().is_empty())
+                            {
+                                self.check_free(id, span, self_ref.path());
+                            }
+                        }
+                    }
+                    ir::TerminatorKind::Drop {
+                        place,
+                        ty,
+                        replace: Some(value),
+                        ..
+                    } => {
+                        // `self.field = mem::uninitialized();`
+                        if let Some(path) = self.self_refs.field_of_place(*place) {
+                            if self.is_uninit(value) {
+                                self.check_invalidation(id, span, &path, ty, Invalidation::Uninit);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            for access in dangling_accesses(tcx, self.param_env, self.body, &self.dangling_params) {
+                self.status.add(
+                    BehaviorFlag::MAY_DANGLE_ACCESS,
+                    format!(
+                        "`#[may_dangle]` parameter `{}` is accessed through `{}`",
+                        access.param, access.callee
+                    ),
+                );
+                self.status.uses.push(access.span);
+            }
+
+            self.status
+        }
+
+        fn check_invalidation(
+            &mut self,
+            id: usize,
+            span: Span,
+            path: &[usize],
+            ty: Ty<'tcx>,
+            invalidation: Invalidation,
+        ) {
+            if let Invalidation::Moved(value) = invalidation {
+                if self.is_forgotten(value) {
+                    // The field still owns the value
+                    return;
+                }
+            }
+
+            let aftermath = self.aftermath(id, path);
+            let field = self.field_name(path);
+            let mut detected = false;
+            match invalidation {
+                Invalidation::Moved(_) => {
+                    if !aftermath.uses.is_empty() {
+                        self.status.add(
+                            BehaviorFlag::MOVED_READ,
+                            format!("`{}` is used after being moved out", field),
+                        );
+                        detected = true;
+                    }
+                    if aftermath.dropped {
+                        self.status.add(
+                            BehaviorFlag::DOUBLE_DROP,
+                            format!("`{}` is moved out and dropped again", field),
+                        );
+                        detected = true;
+                    }
+                }
+                Invalidation::Uninit => {
+                    if !aftermath.uses.is_empty() {
+                        self.status.add(
+                            BehaviorFlag::UNINIT_READ,
+                            format!("`{}` is used after being uninitialized", field),
+                        );
+                        detected = true;
+                    }
+                    if aftermath.dropped && ty.needs_drop(self.rcx.tcx(), self.param_env) {
+                        self.status.add(
+                            BehaviorFlag::UNINIT_DROP,
+                            format!("`{}` is dropped while uninitialized", field),
+                        );
+                        detected = true;
+                    }
+                }
+            }
+
+            if detected {
+                self.status.invalidations.push(span);
+                self.status.uses.extend(aftermath.uses);
+            }
+        }
+
+        /// Follows the control flow after the invalidation of `path` in block `id`,
+        /// until the field is restored with `ptr::write()` (or an assignment).
+        fn aftermath(&self, id: usize, path: &[usize]) -> Aftermath {
+            let mut aftermath = Aftermath::default();
+            let mut visited = vec![false; self.body.basic_blocks.len()];
+            let mut work_list = self.successors(id);
+            'blocks: while let Some(current) = work_list.pop() {
+                if visited[current] {
+                    continue;
+                }
+                visited[current] = true;
+
+                let block = &self.body.basic_blocks[current];
+                for statement in block.statements.iter() {
+                    if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                        if rvalue_reads(rvalue).into_iter().any(|place| {
+                            self.self_refs
+                                .of_place(place, false)
+                                .map_or(false, |self_ref| self_ref.overlaps(path))
+                        }) {
+                            aftermath.uses.push(statement.source_info.span);
+                        }
+                        if self
+                            .self_refs
+                            .field_of_place(*lhs)
+                            .map_or(false, |lhs_path| path.starts_with(&lhs_path))
+                        {
+                            continue 'blocks;
+                        }
+                    }
+                }
+
+                let terminator = &block.terminator;
+                match &terminator.kind {
+                    ir::TerminatorKind::StaticCall { args, .. }
+                    | ir::TerminatorKind::FnPtr { args, .. } => {
+                        if self.is_restore(terminator, path) {
+                            continue;
+                        }
+                        if args.iter().any(|arg| {
+                            self.self_refs
+                                .of_operand(arg)
+                                .map_or(false, |self_ref| self_ref.overlaps(path))
+                        }) {
+                            aftermath.uses.push(terminator.original.source_info.span);
+                        }
+                    }
+                    ir::TerminatorKind::Drop { place, .. } => {
+                        if self
+                            .self_refs
+                            .field_of_place(*place)
+                            .map_or(false, |drop_path| SelfRef::Addr(drop_path).overlaps(path))
+                        {
+                            aftermath.dropped = true;
+                            continue;
+                        }
+                    }
+                    _ => {
+                        if let mir::TerminatorKind::Return | mir::TerminatorKind::Resume =
+                            terminator.original.kind
+                        {
+                            // Fields of `self` are dropped after `drop()`
+                            aftermath.dropped = true;
+                        }
+                    }
+                }
+                work_list.extend(self.successors(current));
+            }
+            aftermath
+        }
+
+        /// `ptr::write()` or `mem::replace()` of an initialized value to the field
+        fn is_restore(&self, terminator: &ir::Terminator<'tcx>, path: &[usize]) -> bool {
+            let ext = self.rcx.tcx().ext();
+            match &terminator.kind {
+                ir::TerminatorKind::StaticCall {
+                    callee_did, args, ..
+                } => {
+                    (ext.match_def_path(*callee_did, &PTR_WRITE)
+                        || ext.match_def_path(*callee_did, &PTR_DIRECT_WRITE)
+                        || ext.match_def_path(*callee_did, &MEM_REPLACE))
+                        && matches!(
+                            args.get(0).and_then(|arg| self.self_refs.of_operand(arg)),
+                            Some(SelfRef::Addr(dest_path)) if path.starts_with(&dest_path) && !dest_path.is_empty()
+                        )
+                        && !args.get(1).map_or(false, |value| self.is_uninit(value))
+                }
+                _ => false,
+            }
+        }
+
+        /// The memory owned by the field at `path` is freed in block `id`.
+        fn check_free(&mut self, id: usize, span: Span, path: &[usize]) {
+            let tcx = self.rcx.tcx();
+            let field = self.field_name(path);
+
+            // Safe code can store any pointer in a public field
+            if_chain! {
+                if let ty::TyKind::Adt(adt_def, substs) = self.self_ty.kind();
+                if adt_def.is_struct() && tcx.visibility(adt_def.did) == ty::Visibility::Public;
+                if let Some(field_def) = adt_def.non_enum_variant().fields.get(path[0]);
+                if field_def.vis == ty::Visibility::Public;
+                let field_ty = field_def.ty(tcx, substs);
+                if field_ty.is_unsafe_ptr() || field_ty
+                    .ty_adt_def()
+                    .map_or(false, |adt_def| tcx.ext().match_def_path(adt_def.did, &NON_NULL));
+                then {
+                    self.status.add(
+                        BehaviorFlag::PUB_FIELD_FREE,
+                        format!("memory behind public field `{}` is freed", field),
+                    );
+                    self.status.invalidations.push(span);
+                }
+            }
+
+            let generic_calls = self.generic_calls_after(id);
+            if !generic_calls.is_empty() {
+                self.status.add(
+                    BehaviorFlag::GENERIC_AFTER_FREE,
+                    format!("generic code is called after `{}` is freed", field),
+                );
+                self.status.invalidations.push(span);
+                self.status.generic_calls.extend(generic_calls);
+            }
+        }
+
+        /// Calls of unresolvable generic code reachable from block `id` that take `self` (or its fields).
+        fn generic_calls_after(&self, id: usize) -> Vec<Span> {
+            let mut generic_calls = Vec::new();
+            let mut visited = vec![false; self.body.basic_blocks.len()];
+            let mut work_list = self.successors(id);
+            while let Some(current) = work_list.pop() {
+                if visited[current] {
+                    continue;
+                }
+                visited[current] = true;
+
+                let terminator = &self.body.basic_blocks[current].terminator;
+                if self.is_generic_call_on_self(terminator) {
+                    generic_calls.push(terminator.original.source_info.span);
+                }
+                work_list.extend(self.successors(current));
+            }
+            generic_calls
+        }
+
+        fn is_generic_call_on_self(&self, terminator: &ir::Terminator<'tcx>) -> bool {
+            let takes_self = match &terminator.kind {
+                ir::TerminatorKind::StaticCall { args, .. }
+                | ir::TerminatorKind::FnPtr { args, .. } => args
+                    .iter()
+                    .any(|arg| self.self_refs.of_operand(arg).is_some()),
+                ir::TerminatorKind::Drop { place, .. } => {
+                    self.self_refs.of_place(*place, false).is_some()
+                }
+                _ => false,
+            };
+            takes_self && self.calls_user_code(terminator)
+        }
+
+        /// Unresolvable generic code, trait objects and function pointers are potentially user-provided.
+        fn calls_user_code(&self, terminator: &ir::Terminator<'tcx>) -> bool {
+            let tcx = self.rcx.tcx();
+            match &terminator.kind {
+                ir::TerminatorKind::StaticCall {
+                    callee_did,
+                    callee_substs,
+                    ..
+                } => {
+                    if GENERIC_FN_LIST.contains(&tcx.ext().get_def_path(*callee_did)) {
+                        return true;
+                    }
+                    match Instance::resolve(tcx, self.param_env, *callee_did, callee_substs) {
+                        Err(_e) => {
+                            log_err!(ResolveError);
+                            false
+                        }
+                        Ok(Some(instance)) => matches!(instance.def, InstanceDef::Virtual(..)),
+                        Ok(None) => true,
+                    }
+                }
+                ir::TerminatorKind::FnPtr { .. } => true,
+                ir::TerminatorKind::Drop { ty, .. } => {
+                    has_unresolvable_drop(tcx, self.param_env, ty)
+                }
+                _ => false,
+            }
+        }
+
+        /// Successors of a block. Only user-provided code is assumed to panic,
+        /// so the unwind edge is followed only from calls of user-provided code.
+        fn successors(&self, id: usize) -> Vec<usize> {
+            let terminator = &self.body.basic_blocks[id].terminator;
+            let unwind = match terminator.kind {
+                ir::TerminatorKind::StaticCall { cleanup, .. }
+                | ir::TerminatorKind::FnPtr { cleanup, .. } => cleanup,
+                ir::TerminatorKind::Drop { unwind, .. } => unwind,
+                _ => None,
+            };
+            let mut successors = self.body.next(id);
+            if unwind.is_some() && !self.calls_user_code(terminator) {
+                successors.retain(|&next| Some(next) != unwind);
+            }
+            successors
+        }
+
+        /// Returns true if `local` is (transitively) moved, copied or derived from one of `targets`.
+        fn derives_from(&self, local: Local, targets: &[Local]) -> bool {
+            targets.contains(&local)
+                || origin_chain(&self.origins, local, |origin| origin)
+                    .iter()
+                    .any(|origin| targets.contains(origin))
+        }
+
+        fn is_uninit(&self, operand: &Operand<'tcx>) -> bool {
+            match operand.place() {
+                Some(place) if place.projection.is_empty() => {
+                    self.derives_from(place.local, &self.uninit_locals)
+                }
+                _ => false,
+            }
+        }
+
+        /// The moved value is passed to `mem::forget()` or `ManuallyDrop::new()`.
+        fn is_forgotten(&self, value: Local) -> bool {
+            let ext = self.rcx.tcx().ext();
+            self.body
+                .terminators()
+                .any(|terminator| match &terminator.kind {
+                    ir::TerminatorKind::StaticCall {
+                        callee_did, args, ..
+                    } => {
+                        (ext.match_def_path(*callee_did, &MEM_FORGET)
+                            || ext.match_def_path(*callee_did, &MANUALLY_DROP_NEW))
+                            && args
+                                .get(0)
+                                .and_then(|arg| arg.place())
+                                .map_or(false, |place| self.derives_from(place.local, &[value]))
+                    }
+                    _ => false,
+                })
+        }
+
+        /// `self.a.b` style name of a field path
+        fn field_name(&self, path: &[usize]) -> String {
+            let tcx = self.rcx.tcx();
+            let mut name = String::from("self");
+            let mut ty = Some(self.self_ty);
+            for &idx in path {
+                match ty.map(|ty| ty.kind()) {
+                    Some(ty::TyKind::Adt(adt_def, substs)) if adt_def.is_struct() => {
+                        let field_def = &adt_def.non_enum_variant().fields[idx];
+                        name += &format!(".{}", field_def.ident);
+                        ty = Some(field_def.ty(tcx, substs));
+                    }
+                    Some(ty::TyKind::Tuple(substs)) => {
+                        name += &format!(".{}", idx);
+                        ty = substs.types().nth(idx);
+                    }
+                    _ => {
+                        name += &format!(".{}", idx);
+                        ty = None;
+                    }
+                }
+            }
+            name
+        }
+    }
+
+    /// Places that are read by an rvalue (taking a reference is not a read)
+    fn rvalue_reads<'tcx>(rvalue: &Rvalue<'tcx>) -> Vec<Place<'tcx>> {
+        match rvalue {
+            Rvalue::Use(operand)
+            | Rvalue::Repeat(operand, _)
+            | Rvalue::Cast(_, operand, _)
+            | Rvalue::UnaryOp(_, operand) => operand.place().into_iter().collect(),
+            Rvalue::BinaryOp(_, box (lhs, rhs)) | Rvalue::CheckedBinaryOp(_, box (lhs, rhs)) => {
+                lhs.place().into_iter().chain(rhs.place()).collect()
+            }
+            Rvalue::Aggregate(_, operands) => operands
+                .iter()
+                .filter_map(|operand| operand.place())
+                .collect(),
+            Rvalue::Len(place) | Rvalue::Discriminant(place) => vec![*place],
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Unsafe destructor behavior kind.
+// Used to associate each Unsafe-Destructor bug report with its cause.
+bitflags! {
+    #[derive(Default)]
+    pub struct BehaviorFlag: u8 {
+        /// A field is used after being moved out by `ptr::read()`
+        const MOVED_READ = 0b00000001;
+        /// A field is moved out by `ptr::read()` and dropped again
+        const DOUBLE_DROP = 0b00000010;
+        /// A field is used after being overwritten with uninitialized memory
+        const UNINIT_READ = 0b00000100;
+        /// A field is dropped while holding uninitialized memory
+        const UNINIT_DROP = 0b00001000;
+        /// Generic code is called with `self` after the memory owned by `self` is freed
+        const GENERIC_AFTER_FREE = 0b00010000;
+        /// The memory behind a public pointer field is freed
+        const PUB_FIELD_FREE = 0b00100000;
+        /// A value of a `#[may_dangle]` type parameter is accessed
+        const MAY_DANGLE_ACCESS = 0b01000000;
+    }
+}
+
+impl IntoReportLevel for BehaviorFlag {
+    fn report_level(&self) -> ReportLevel {
+        use BehaviorFlag as Flag;
+
+        let high = Flag::MOVED_READ
+            | Flag::DOUBLE_DROP
+            | Flag::UNINIT_READ
+            | Flag::UNINIT_DROP
+            | Flag::MAY_DANGLE_ACCESS;
+        let med = Flag::GENERIC_AFTER_FREE | Flag::PUB_FIELD_FREE;
+
+        if !(*self & high).is_empty() {
+            ReportLevel::Error
+        } else if !(*self & med).is_empty() {
+            ReportLevel::Warning
+        } else {
+            ReportLevel::Info
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Tracks which locals of `Drop::drop(&mut self)` point into (or were copied from) the fields of `self`.
+
+use rustc_middle::mir::{Operand, Place, ProjectionElem, Rvalue, StatementKind};
+use rustc_middle::ty::TyKind;
+
+use crate::ir;
+
+/// `self` of `Drop::drop(&mut self)`
+pub const SELF_LOCAL: usize = 1;
+
+/// The field of `self` that a local is derived from.
+/// A field is identified by the path of field indices from `self` (e.g. `self.a.b` => `[0, 1]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelfRef {
+    /// Address of the field (`&mut self.a`, `&raw const self.a`)
+    Addr(Vec<usize>),
+    /// Value of the field, or a pointer derived from it (`self.ptr`, `self.ptr.add(1)`, `self.vec.as_ptr()`)
+    Value(Vec<usize>),
+}
+
+impl SelfRef {
+    pub fn path(&self) -> &[usize] {
+        match self {
+            SelfRef::Addr(path) | SelfRef::Value(path) => path,
+        }
+    }
+
+    /// Returns true if both refer to the same memory of a field (the whole `self` excluded).
+    pub fn overlaps(&self, path: &[usize]) -> bool {
+        let own_path = self.path();
+        !own_path.is_empty()
+            && !path.is_empty()
+            && (own_path.starts_with(path) || path.starts_with(own_path))
+    }
+}
+
+/// Memory that a place refers to
+enum Lvalue {
+    /// A local itself
+    Local(SelfRef),
+    /// A field of `self`
+    Field(Vec<usize>),
+    /// Memory behind a pointer that is stored in a field of `self`
+    Pointee(Vec<usize>),
+}
+
+pub struct SelfRefs {
+    refs: Vec<Option<SelfRef>>,
+}
+
+impl SelfRefs {
+    pub fn new(body: &ir::Body<'_>) -> Self {
+        let mut self_refs = SelfRefs {
+            refs: vec![None; body.local_decls.len()],
+        };
+        if SELF_LOCAL < body.local_decls.len() {
+            self_refs.refs[SELF_LOCAL] = Some(SelfRef::Addr(Vec::new()));
+            self_refs.collect(body);
+        }
+        self_refs
+    }
+
+    pub fn of_operand(&self, operand: &Operand<'_>) -> Option<SelfRef> {
+        operand
+            .place()
+            .and_then(|place| self.of_place(place, false))
+    }
+
+    /// The field of `self` that a place reads from, or takes the address of (`is_ref`).
+    pub fn of_place(&self, place: Place<'_>, is_ref: bool) -> Option<SelfRef> {
+        match (self.lvalue(place)?, is_ref) {
+            (Lvalue::Local(self_ref), _) => Some(self_ref),
+            (Lvalue::Field(path), true) => Some(SelfRef::Addr(path)),
+            (Lvalue::Field(path), false) | (Lvalue::Pointee(path), _) => {
+                if path.is_empty() {
+                    None
+                } else {
+                    Some(SelfRef::Value(path))
+                }
+            }
+        }
+    }
+
+    /// The field of `self` that a place writes to, excluding the memory behind pointers.
+    pub fn field_of_place(&self, place: Place<'_>) -> Option<Vec<usize>> {
+        match self.lvalue(place)? {
+            Lvalue::Field(path) if !path.is_empty() => Some(path),
+            _ => None,
+        }
+    }
+
+    fn lvalue(&self, place: Place<'_>) -> Option<Lvalue> {
+        let mut lvalue = Lvalue::Local(self.refs[place.local.index()].clone()?);
+        // Fields are not followed after indexing into an array or a slice
+        let mut exact = true;
+        for elem in place.projection.iter() {
+            lvalue = match (lvalue, elem) {
+                (Lvalue::Local(SelfRef::Addr(path)), ProjectionElem::Deref) => Lvalue::Field(path),
+                (Lvalue::Local(SelfRef::Value(path)), ProjectionElem::Deref)
+                | (Lvalue::Field(path), ProjectionElem::Deref)
+                | (Lvalue::Pointee(path), _) => Lvalue::Pointee(path),
+                (Lvalue::Field(mut path), ProjectionElem::Field(field, _)) => {
+                    if exact {
+                        path.push(field.index());
+                    }
+                    Lvalue::Field(path)
+                }
+                (Lvalue::Field(path), ProjectionElem::Downcast(..)) => Lvalue::Field(path),
+                (Lvalue::Field(path), _) => {
+                    exact = false;
+                    Lvalue::Field(path)
+                }
+                // Projections into a local aggregate
+                (Lvalue::Local(_), _) => return None,
+            };
+        }
+        Some(lvalue)
+    }
+
+    /// Locals are assigned once in most cases, so the first assignment wins.
+    fn collect(&mut self, body: &ir::Body<'_>) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in body.basic_blocks.iter() {
+                for statement in block.statements.iter() {
+                    if let StatementKind::Assign(box (lhs, rvalue)) = &statement.kind {
+                        if !lhs.projection.is_empty() || self.refs[lhs.local.index()].is_some() {
+                            continue;
+                        }
+                        let self_ref = match rvalue {
+                            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => {
+                                self.of_place(*place, true)
+                            }
+                            Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) => {
+                                self.of_operand(operand)
+                            }
+                            _ => None,
+                        };
+                        if let Some(self_ref) = self_ref {
+                            self.refs[lhs.local.index()] = Some(self_ref);
+                            changed = true;
+                        }
+                    }
+                }
+
+                // Pointers returned from methods (`as_ptr()`, `add()`, ...) point into their receiver
+                if let ir::TerminatorKind::StaticCall {
+                    args,
+                    destination: Some((place, _)),
+                    ..
+                } = &block.terminator.kind
+                {
+                    let dest_ty = body.local_decls[place.local.index()].ty;
+                    if place.projection.is_empty()
+                        && self.refs[place.local.index()].is_none()
+                        && matches!(dest_ty.kind(), TyKind::Ref(..) | TyKind::RawPtr(_))
+                    {
+                        if let Some(receiver) = args.get(0).and_then(|arg| self.of_operand(arg)) {
+                            if !receiver.path().is_empty() {
+                                self.refs[place.local.index()] =
+                                    Some(SelfRef::Value(receiver.path().to_vec()));
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -16,6 +16,7 @@ use rustc_version::VersionMeta;
 use wait_timeout::ChildExt;
 
 use rudra::log::{self, Verbosity};
+use rudra::report::baseline::Baseline;
 use rudra::{progress_error, progress_info};
 
 const CARGO_RUDRA_HELP: &str = r#"Tests crates with Rudra
@@ -24,6 +25,8 @@ Usage:
 
 Common options:
     -h, --help               Print this message
+    --baseline <path>        Record the reports in <path> if it doesn't exist,
+                             and only print reports that are not in <path> otherwise
 
 Other [options] are the same as `cargo check`. Everything after the first "--" is
 passed verbatim to Rudra.
@@ -305,6 +308,21 @@ fn in_cargo_rudra() {
     // Ensure `lib` is compiled before `bin`
     targets.sort_by_key(|target| TargetKind::from(target) as u8);
 
+    // Use the baseline if it exists, or record the reports of this run into it
+    let baseline = get_arg_flag_value("--baseline").map(|path| {
+        env::current_dir()
+            .expect("current directory is invalid")
+            .join(path)
+    });
+    let fingerprint_dir = match &baseline {
+        Some(path) if !path.exists() => {
+            let dir = env::temp_dir().join(format!("rudra-fingerprints-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).expect("failed to create fingerprint directory");
+            Some(dir)
+        }
+        _ => None,
+    };
+
     for target in targets {
         // Skip `cargo rudra`
         let mut args = std::env::args().skip(2);
@@ -352,6 +370,14 @@ fn in_cargo_rudra() {
             if arg == "--" {
                 break;
             }
+            if arg == "--baseline" {
+                // Skip the value as well
+                args.next();
+                continue;
+            }
+            if arg.starts_with("--baseline=") {
+                continue;
+            }
             cmd.arg(arg);
         }
 
@@ -372,6 +398,16 @@ fn in_cargo_rudra() {
             );
         }
 
+        match (&baseline, &fingerprint_dir) {
+            (_, Some(dir)) => {
+                cmd.env("RUDRA_FINGERPRINT_DIR", dir);
+            }
+            (Some(path), None) => {
+                cmd.env("RUDRA_BASELINE", path);
+            }
+            (None, None) => (),
+        }
+
         // Serialize the remaining args into a special environment variable.
         // This will be read by `inside_cargo_rustc` when we go to invoke
         // our actual target crate (the binary or the test we are running).
@@ -416,6 +452,20 @@ fn in_cargo_rudra() {
             }
         };
     }
+
+    if let (Some(path), Some(dir)) = (&baseline, &fingerprint_dir) {
+        let merged = Baseline::load_fingerprint_dir(dir)
+            .unwrap_or_else(|e| show_error(format!("failed to collect fingerprints: {}", e)));
+        merged
+            .save(path)
+            .unwrap_or_else(|e| show_error(format!("failed to write baseline: {}", e)));
+        std::fs::remove_dir_all(dir).ok();
+        progress_info!(
+            "Baseline with {} reports is written to {}",
+            merged.len(),
+            path.display()
+        );
+    }
 }
 
 fn inside_cargo_rustc() {
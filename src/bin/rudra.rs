@@ -9,13 +9,17 @@ extern crate rustc_interface;
 extern crate log;
 
 use std::env;
+use std::path::Path;
 
 use rustc_driver::Compilation;
 use rustc_interface::{interface::Compiler, Queries};
 
+use rudra::config::{init_user_config, UserConfig};
 use rudra::log::Verbosity;
 use rudra::report::{default_report_logger, init_report_logger, ReportLevel};
-use rudra::{analyze, compile_time_sysroot, progress_info, RudraConfig, RUDRA_DEFAULT_ARGS};
+use rudra::{
+    analyze, compile_time_sysroot, progress_info, RudraConfig, SendSyncMode, UnsafeDataflowMode,
+};
 
 struct RudraCompilerCalls {
     config: RudraConfig,
@@ -76,10 +80,13 @@ fn run_compiler(
 
     // Some options have different defaults in Rudra than in plain rustc; apply those by making
     // them the first arguments after the binary name (but later arguments can overwrite them).
-    args.splice(
-        1..1,
-        rudra::RUDRA_DEFAULT_ARGS.iter().map(ToString::to_string),
-    );
+    let crate_root_source = crate_root(&args).and_then(|path| std::fs::read_to_string(path).ok());
+    let default_args: Vec<String> = rudra::RUDRA_DEFAULT_ARGS
+        .iter()
+        .map(ToString::to_string)
+        .chain(rudra::rudra_crate_attr_args(crate_root_source.as_deref()))
+        .collect();
+    args.splice(1..1, default_args);
 
     // Invoke compiler, and handle return code.
     let exit_code = rustc_driver::catch_with_exit_code(move || {
@@ -89,6 +96,14 @@ fn run_compiler(
     exit_code
 }
 
+/// Finds the crate root among the rustc arguments (the input `.rs` file).
+fn crate_root(args: &[String]) -> Option<&Path> {
+    args.iter()
+        .skip(1)
+        .map(Path::new)
+        .find(|path| path.extension().map_or(false, |ext| ext == "rs") && path.is_file())
+}
+
 fn parse_config() -> (RudraConfig, Vec<String>) {
     // collect arguments
     let mut config = RudraConfig::default();
@@ -104,15 +119,42 @@ fn parse_config() -> (RudraConfig, Vec<String>) {
             }
             "-Zrudra-enable-send-sync-variance" => config.send_sync_variance_enabled = true,
             "-Zrudra-disable-send-sync-variance" => config.send_sync_variance_enabled = false,
+            "-Zrudra-send-sync-mode=strict" => config.send_sync_mode = SendSyncMode::Strict,
+            "-Zrudra-send-sync-mode=relaxed" => config.send_sync_mode = SendSyncMode::Relaxed,
             "-Zrudra-enable-unsafe-dataflow" => config.unsafe_dataflow_enabled = true,
             "-Zrudra-disable-unsafe-dataflow" => config.unsafe_dataflow_enabled = false,
+            "-Zrudra-enable-uninit-exposure" => config.uninit_exposure_enabled = true,
+            "-Zrudra-disable-uninit-exposure" => config.uninit_exposure_enabled = false,
+            "-Zrudra-enable-higher-order-invariant" => config.higher_order_invariant_enabled = true,
+            "-Zrudra-disable-higher-order-invariant" => {
+                config.higher_order_invariant_enabled = false
+            }
+            "-Zrudra-unsafe-dataflow-mode=reachability" => {
+                config.unsafe_dataflow_mode = UnsafeDataflowMode::Reachability
+            }
+            "-Zrudra-unsafe-dataflow-mode=place" => {
+                config.unsafe_dataflow_mode = UnsafeDataflowMode::PlaceSensitive
+            }
+            "-Zrudra-unsafe-dataflow-mode=unwind" => {
+                config.unsafe_dataflow_mode = UnsafeDataflowMode::Unwind
+            }
             "-v" => config.verbosity = Verbosity::Verbose,
             "-vv" => config.verbosity = Verbosity::Trace,
             "-Zsensitivity-high" => config.report_level = ReportLevel::Error,
             "-Zsensitivity-med" => config.report_level = ReportLevel::Warning,
             "-Zsensitivity-low" => config.report_level = ReportLevel::Info,
             _ => {
-                rustc_args.push(arg);
+                if let Some(path) = arg.strip_prefix("-Zrudra-config=") {
+                    match UserConfig::load(Path::new(path)) {
+                        Ok(user_config) => init_user_config(user_config),
+                        Err(e) => {
+                            eprintln!("Rudra failed to load config: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                } else {
+                    rustc_args.push(arg);
+                }
             }
         }
     }
@@ -143,9 +185,6 @@ fn main() {
             }
         }
 
-        // Finally, add the default flags all the way in the beginning, but after the binary name.
-        rustc_args.splice(1..1, RUDRA_DEFAULT_ARGS.iter().map(ToString::to_string));
-
         debug!("rustc arguments: {:?}", &rustc_args);
         run_compiler(rustc_args, &mut RudraCompilerCalls::new(config))
     };
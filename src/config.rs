@@ -0,0 +1,154 @@
+//! User configuration file loaded with `-Zrudra-config=<path>`.
+//!
+//! ```toml
+//! [[unsafe_dataflow.strong_bypass]]
+//! path = "my_arena::Arena::duplicate"
+//! kind = "READ_FLOW"           # one of `UnsafeDataflowBehaviorFlag`
+//!
+//! [[unsafe_dataflow.weak_bypass]]
+//! path = "my_vec::RawVec::slot_ref"
+//! kind = "user:ArenaSlotRef"   # `user:` followed by a name defines a user category
+//!
+//! [unsafe_dataflow]
+//! sink = ["my_callback::invoke"]
+//!
+//! [[unsafe_trait]]
+//! path = "bytemuck::pod::Pod"
+//! param_bound = ["bytemuck::pod::Pod"]  # each generic parameter of the ADT must be bounded by
+//! field_bound = ["bytemuck::pod::Pod"]  # each field type of the ADT must implement
+//! ```
+//!
+//! Paths are def paths as printed by the paths discovery (see `paths.rs`),
+//! joined with `::`. Entries are merged with the built-in path sets.
+//! A `kind` that is neither a flag name nor prefixed with `user:` is rejected,
+//! so that a misspelled flag (e.g., `READ_FLOWS`) doesn't silently become a category.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+
+use crate::analysis::UnsafeDataflowBehaviorFlag;
+
+static USER_CONFIG: OnceCell<UserConfig> = OnceCell::new();
+
+/// Prefix of the user category names in `BypassConfig::kind`
+const USER_CATEGORY_PREFIX: &str = "user:";
+
+#[derive(Debug, Snafu)]
+pub enum ConfigError {
+    #[snafu(display("Failed to read config file {}: {}", path.display(), source))]
+    ReadConfig { path: PathBuf, source: io::Error },
+    #[snafu(display("Failed to parse config file {}: {}", path.display(), source))]
+    ParseConfig {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[snafu(display(
+        "Invalid bypass kind `{}` for `{}` in {}: expected a behavior flag name or `user:<category>`",
+        kind,
+        bypass,
+        path.display()
+    ))]
+    InvalidBypassKind {
+        path: PathBuf,
+        bypass: String,
+        kind: String,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserConfig {
+    pub unsafe_dataflow: UnsafeDataflowConfig,
+    pub unsafe_trait: Vec<UnsafeTraitConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnsafeDataflowConfig {
+    pub strong_bypass: Vec<BypassConfig>,
+    pub weak_bypass: Vec<BypassConfig>,
+    /// Functions treated like a call to unresolvable generic code
+    pub sink: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BypassConfig {
+    pub path: String,
+    /// Name of an `UnsafeDataflowBehaviorFlag` constant, or `user:` followed by a user category name
+    pub kind: String,
+}
+
+/// The safety contract of an unsafe trait, checked on its impls for ADTs
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnsafeTraitConfig {
+    pub path: String,
+    /// Traits that each generic parameter of the ADT must be bounded by
+    #[serde(default)]
+    pub param_bound: Vec<String>,
+    /// Traits that each field type of the ADT must implement
+    #[serde(default)]
+    pub field_bound: Vec<String>,
+}
+
+impl BypassConfig {
+    pub fn behavior_flag(&self) -> UnsafeDataflowBehaviorFlag {
+        UnsafeDataflowBehaviorFlag::from_name(&self.kind)
+            .unwrap_or(UnsafeDataflowBehaviorFlag::USER_DEFINED)
+    }
+
+    /// Returns the category name if `kind` is `user:<category>`.
+    pub fn user_category(&self) -> Option<&str> {
+        self.kind
+            .strip_prefix(USER_CATEGORY_PREFIX)
+            .filter(|category| !category.is_empty())
+    }
+
+    fn is_valid(&self) -> bool {
+        UnsafeDataflowBehaviorFlag::from_name(&self.kind).is_some()
+            || self.user_category().is_some()
+    }
+}
+
+impl UserConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).context(ReadConfig { path })?;
+        let config: UserConfig = toml::from_str(&content).context(ParseConfig { path })?;
+
+        let unsafe_dataflow = &config.unsafe_dataflow;
+        if let Some(bypass) = unsafe_dataflow
+            .strong_bypass
+            .iter()
+            .chain(unsafe_dataflow.weak_bypass.iter())
+            .find(|bypass| !bypass.is_valid())
+        {
+            return InvalidBypassKind {
+                path,
+                bypass: bypass.path.clone(),
+                kind: bypass.kind.clone(),
+            }
+            .fail();
+        }
+
+        Ok(config)
+    }
+}
+
+/// Sets the global user config. Must be called before the analysis starts.
+pub fn init_user_config(config: UserConfig) {
+    USER_CONFIG
+        .set(config)
+        .map_err(|_| ())
+        .expect("The user config is already initialized");
+}
+
+/// Returns the global user config, or an empty config if none was given.
+pub fn user_config() -> &'static UserConfig {
+    USER_CONFIG.get_or_init(UserConfig::default)
+}
@@ -14,6 +14,7 @@ use snafu::Snafu;
 use crate::ir;
 use crate::prelude::*;
 use crate::report::ReportLevel;
+use crate::suppression::Suppressions;
 use crate::visitor::{create_adt_impl_map, AdtImplMap, RelatedFnCollector, RelatedItemMap};
 
 #[derive(Debug, Snafu, Clone)]
@@ -39,7 +40,10 @@ pub struct RudraCtxtOwner<'tcx> {
     translation_cache: DashMap<DefId, Rc<TranslationResult<'tcx, ir::Body<'tcx>>>>,
     related_item_cache: RelatedItemMap,
     adt_impl_cache: AdtImplMap<'tcx>,
+    /// `impl`s of ADTs from other crates, loaded from crate metadata on demand
+    external_adt_impl_cache: DashMap<DefId, Rc<Vec<DefId>>>,
     report_level: ReportLevel,
+    suppressions: Suppressions,
 }
 
 /// Visit MIR body and returns a Rudra IR function
@@ -52,7 +56,9 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
             translation_cache: DashMap::new(),
             related_item_cache: RelatedFnCollector::collect(tcx),
             adt_impl_cache: create_adt_impl_map(tcx),
+            external_adt_impl_cache: DashMap::new(),
             report_level,
+            suppressions: Suppressions::collect(tcx),
         }
     }
 
@@ -95,7 +101,7 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
         let basic_blocks: Vec<_> = body
             .basic_blocks()
             .iter()
-            .map(|basic_block| self.translate_basic_block(basic_block))
+            .map(|basic_block| self.translate_basic_block(body, basic_block))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(ir::Body {
@@ -108,6 +114,7 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
 
     fn translate_basic_block(
         &self,
+        body: &mir::Body<'tcx>,
         basic_block: &mir::BasicBlockData<'tcx>,
     ) -> TranslationResult<'tcx, ir::BasicBlock<'tcx>> {
         let statements = basic_block
@@ -117,6 +124,7 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
             .collect::<Vec<_>>();
 
         let terminator = self.translate_terminator(
+            body,
             basic_block
                 .terminator
                 .as_ref()
@@ -132,6 +140,7 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
 
     fn translate_terminator(
         &self,
+        body: &mir::Body<'tcx>,
         terminator: &mir::Terminator<'tcx>,
     ) -> TranslationResult<'tcx, ir::Terminator<'tcx>> {
         Ok(ir::Terminator {
@@ -150,33 +159,48 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
                         .clone()
                         .map(|(place, block)| (place, block.index()));
 
-                    if let mir::Operand::Constant(box func) = func_operand {
-                        let func_ty = func.literal.ty();
-                        match func_ty.kind() {
-                            TyKind::FnDef(def_id, callee_substs) => {
-                                ir::TerminatorKind::StaticCall {
-                                    callee_did: *def_id,
-                                    callee_substs,
-                                    args: args.clone(),
-                                    cleanup,
-                                    destination,
-                                }
-                            }
-                            TyKind::FnPtr(_) => ir::TerminatorKind::FnPtr {
-                                value: func.literal.clone(),
-                            },
-                            _ => panic!("invalid callee of type {:?}", func_ty),
-                        }
-                    } else {
-                        ir::TerminatorKind::Unimplemented("non-constant function call".into())
+                    // Function items can also be stored in locals (`let f = foo; f()`)
+                    let func_ty = func_operand.ty(body, self.tcx);
+                    match func_ty.kind() {
+                        TyKind::FnDef(def_id, callee_substs) => ir::TerminatorKind::StaticCall {
+                            callee_did: *def_id,
+                            callee_substs,
+                            args: args.clone(),
+                            cleanup,
+                            destination,
+                        },
+                        TyKind::FnPtr(_) => ir::TerminatorKind::FnPtr {
+                            func: func_operand.clone(),
+                            args: args.clone(),
+                            cleanup,
+                            destination,
+                        },
+                        _ => panic!("invalid callee of type {:?}", func_ty),
                     }
                 }
-                TerminatorKind::Drop { .. } | TerminatorKind::DropAndReplace { .. } => {
-                    // TODO: implement Drop and DropAndReplace terminators
-                    ir::TerminatorKind::Unimplemented(
-                        format!("TODO terminator: {:?}", terminator).into(),
-                    )
-                }
+                TerminatorKind::Drop {
+                    place,
+                    target,
+                    unwind,
+                } => ir::TerminatorKind::Drop {
+                    place: *place,
+                    ty: place.ty(body, self.tcx).ty,
+                    target: target.index(),
+                    unwind: unwind.map(|block| block.index()),
+                    replace: None,
+                },
+                TerminatorKind::DropAndReplace {
+                    place,
+                    value,
+                    target,
+                    unwind,
+                } => ir::TerminatorKind::Drop {
+                    place: *place,
+                    ty: place.ty(body, self.tcx).ty,
+                    target: target.index(),
+                    unwind: unwind.map(|block| block.index()),
+                    replace: Some(value.clone()),
+                },
                 _ => ir::TerminatorKind::Unimplemented(
                     format!("Unknown terminator: {:?}", terminator).into(),
                 ),
@@ -214,7 +238,50 @@ impl<'tcx> RudraCtxtOwner<'tcx> {
         self.adt_impl_cache.get(adt_did)
     }
 
+    /// Inherent `impl`s and `Deref`/`DerefMut` `impl`s of an ADT defined in another crate,
+    /// i.e., the `impl`s that provide the `&self` APIs of the ADT.
+    pub fn external_adt_impls(&self, adt_did: DefId) -> Rc<Vec<DefId>> {
+        self.external_adt_impl_cache
+            .entry(adt_did)
+            .or_insert_with(|| {
+                let tcx = self.tcx;
+                let mut impl_dids: Vec<DefId> = tcx.inherent_impls(adt_did).to_vec();
+
+                let lang_items = tcx.lang_items();
+                let adt_ty = tcx.type_of(adt_did);
+                for &trait_did in [lang_items.deref_trait(), lang_items.deref_mut_trait()]
+                    .iter()
+                    .flatten()
+                {
+                    // Blanket impls (e.g. `impl Deref for &T`) are relevant to every type
+                    tcx.for_each_relevant_impl(trait_did, adt_ty, |impl_did| {
+                        if tcx
+                            .type_of(impl_did)
+                            .ty_adt_def()
+                            .map(|adt_def| adt_def.did)
+                            == Some(adt_did)
+                        {
+                            impl_dids.push(impl_did);
+                        }
+                    });
+                }
+                Rc::new(impl_dids)
+            })
+            .clone()
+    }
+
     pub fn report_level(&self) -> ReportLevel {
         self.report_level
     }
+
+    /// Returns true if the reports of `analyzer` on `item` are suppressed with `rudra::allow`
+    pub fn is_suppressed(&self, analyzer: &str, item: DefId) -> bool {
+        self.suppressions.is_suppressed(self.tcx, analyzer, item)
+    }
+
+    pub fn report_unused_suppressions(&self, enabled_analyzers: &[&str]) {
+        if ReportLevel::Warning >= self.report_level {
+            self.suppressions.report_unused(self.tcx, enabled_analyzers);
+        }
+    }
 }
@@ -5,6 +5,22 @@ use crate::ir;
 pub trait Graph {
     fn len(&self) -> usize;
     fn next(&self, id: usize) -> Vec<usize>;
+
+    /// Returns true if `to` is reachable from `from` (including `from == to`).
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut visited = vec![false; self.len()];
+        let mut work_list = vec![from];
+        while let Some(current) = work_list.pop() {
+            if current == to {
+                return true;
+            }
+            if !visited[current] {
+                visited[current] = true;
+                work_list.extend(self.next(current));
+            }
+        }
+        false
+    }
 }
 
 impl<'tcx> Graph for ir::Body<'tcx> {
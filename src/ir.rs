@@ -27,8 +27,21 @@ pub enum TerminatorKind<'tcx> {
         cleanup: Option<usize>,
         destination: Option<(mir::Place<'tcx>, usize)>,
     },
+    /// Call through a function pointer, whose target is not known statically.
     FnPtr {
-        value: mir::ConstantKind<'tcx>,
+        func: mir::Operand<'tcx>,
+        args: Vec<mir::Operand<'tcx>>,
+        cleanup: Option<usize>,
+        destination: Option<(mir::Place<'tcx>, usize)>,
+    },
+    /// Drops `place` of type `ty`, running its drop glue.
+    /// `DropAndReplace` is translated into a drop with `replace` set to the newly assigned value.
+    Drop {
+        place: mir::Place<'tcx>,
+        ty: Ty<'tcx>,
+        target: usize,
+        unwind: Option<usize>,
+        replace: Option<mir::Operand<'tcx>>,
     },
     Unimplemented(Cow<'static, str>),
 }
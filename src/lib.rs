@@ -4,15 +4,18 @@
 #![feature(try_blocks)]
 #![feature(never_type)]
 
+extern crate rustc_ast;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_hir_pretty;
 extern crate rustc_index;
+extern crate rustc_infer;
 extern crate rustc_interface;
 extern crate rustc_middle;
 extern crate rustc_span;
+extern crate rustc_trait_selection;
 
 #[macro_use]
 extern crate bitflags;
@@ -26,6 +29,7 @@ extern crate log as log_crate;
 mod macros;
 
 mod analysis;
+pub mod config;
 pub mod context;
 pub mod graph;
 pub mod ir;
@@ -34,28 +38,86 @@ pub mod log;
 pub mod paths;
 pub mod prelude;
 pub mod report;
+pub mod suppression;
 pub mod utils;
 pub mod visitor;
 
 use rustc_middle::ty::TyCtxt;
 
-use crate::analysis::{SendSyncVarianceChecker, UnsafeDataflowChecker, UnsafeDestructorChecker};
+use crate::analysis::{
+    HigherOrderInvariantChecker, SendSyncVarianceChecker, UninitExposureChecker,
+    UnsafeDataflowChecker, UnsafeDestructorChecker,
+};
 use crate::context::RudraCtxtOwner;
 use crate::log::Verbosity;
 use crate::report::ReportLevel;
 
+pub use crate::analysis::{SendSyncMode, UnsafeDataflowMode};
+
 // Insert rustc arguments at the beginning of the argument list that Rudra wants to be
 // set per default, for maximal validation power.
 pub static RUDRA_DEFAULT_ARGS: &[&str] =
     &["-Zalways-encode-mir", "-Zmir-opt-level=0", "--cfg=rudra"];
 
+/// Crate attributes that enable `#[cfg_attr(rudra, rudra::allow(...))]`,
+/// paired with the attribute and the list item that a crate root would declare itself.
+static RUDRA_CRATE_ATTRS: &[(&str, &str, &str)] = &[
+    (
+        "-Zcrate-attr=feature(register_tool)",
+        "feature",
+        "register_tool",
+    ),
+    (
+        "-Zcrate-attr=register_tool(rudra)",
+        "register_tool",
+        "rudra",
+    ),
+];
+
+/// Returns the `-Zcrate-attr` arguments that the crate root does not declare yet.
+/// rustc rejects a feature or a tool that is declared twice, so injecting them unconditionally
+/// breaks crates that register `rudra` themselves.
+///
+/// The crate root is scanned textually; attributes hidden behind `cfg_attr` or macros are not recognized.
+pub fn rudra_crate_attr_args(crate_root_source: Option<&str>) -> Vec<String> {
+    let declared = |attr: &str, item: &str| match crate_root_source {
+        Some(source) => crate_attr_lists(source, attr)
+            .any(|list| list.split(',').any(|declared| declared.trim() == item)),
+        None => false,
+    };
+
+    RUDRA_CRATE_ATTRS
+        .iter()
+        .filter(|&&(_, attr, item)| !declared(attr, item))
+        .map(|&(arg, _, _)| arg.to_owned())
+        .collect()
+}
+
+/// Iterates over the argument lists of the inner attribute `#![attr(...)]` in the source, skipping line comments.
+fn crate_attr_lists<'a>(source: &'a str, attr: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//"))
+        .filter_map(move |line| {
+            let rest = line.strip_prefix("#![")?.trim_start();
+            let rest = rest.strip_prefix(attr)?.trim_start();
+            let rest = rest.strip_prefix('(')?;
+            Some(&rest[..rest.find(')')?])
+        })
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct RudraConfig {
     pub verbosity: Verbosity,
     pub report_level: ReportLevel,
     pub unsafe_destructor_enabled: bool,
     pub send_sync_variance_enabled: bool,
+    pub send_sync_mode: SendSyncMode,
     pub unsafe_dataflow_enabled: bool,
+    pub unsafe_dataflow_mode: UnsafeDataflowMode,
+    pub uninit_exposure_enabled: bool,
+    pub higher_order_invariant_enabled: bool,
 }
 
 impl Default for RudraConfig {
@@ -63,9 +125,13 @@ impl Default for RudraConfig {
         RudraConfig {
             verbosity: Verbosity::Normal,
             report_level: ReportLevel::Info,
-            unsafe_destructor_enabled: false,
+            unsafe_destructor_enabled: true,
             send_sync_variance_enabled: true,
+            send_sync_mode: SendSyncMode::Strict,
             unsafe_dataflow_enabled: true,
+            unsafe_dataflow_mode: UnsafeDataflowMode::Reachability,
+            uninit_exposure_enabled: true,
+            higher_order_invariant_enabled: true,
         }
     }
 }
@@ -124,7 +190,7 @@ pub fn analyze<'tcx>(tcx: TyCtxt<'tcx>, config: RudraConfig) {
     // Send/Sync variance analysis
     if config.send_sync_variance_enabled {
         run_analysis("SendSyncVariance", || {
-            let checker = SendSyncVarianceChecker::new(rcx);
+            let checker = SendSyncVarianceChecker::new(rcx, config.send_sync_mode);
             checker.analyze();
         })
     }
@@ -132,8 +198,39 @@ pub fn analyze<'tcx>(tcx: TyCtxt<'tcx>, config: RudraConfig) {
     // Unsafe dataflow analysis
     if config.unsafe_dataflow_enabled {
         run_analysis("UnsafeDataflow", || {
-            let checker = UnsafeDataflowChecker::new(rcx);
+            let checker = UnsafeDataflowChecker::new(rcx, config.unsafe_dataflow_mode);
             checker.analyze();
         })
     }
+
+    // Uninitialized buffer exposure analysis
+    if config.uninit_exposure_enabled {
+        run_analysis("UninitExposure", || {
+            let checker = UninitExposureChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    // Higher-order invariant analysis
+    if config.higher_order_invariant_enabled {
+        run_analysis("HigherOrderInvariant", || {
+            let checker = HigherOrderInvariantChecker::new(rcx);
+            checker.analyze();
+        })
+    }
+
+    let enabled_analyzers: Vec<_> = [
+        (config.unsafe_destructor_enabled, "unsafe_destructor"),
+        (config.send_sync_variance_enabled, "send_sync_variance"),
+        (config.unsafe_dataflow_enabled, "unsafe_dataflow"),
+        (config.uninit_exposure_enabled, "uninit_exposure"),
+        (
+            config.higher_order_invariant_enabled,
+            "higher_order_invariant",
+        ),
+    ]
+    .iter()
+    .filter_map(|&(enabled, name)| enabled.then(|| name))
+    .collect();
+    rcx.report_unused_suppressions(&enabled_analyzers);
 }
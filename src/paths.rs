@@ -6,6 +6,7 @@ use maplit::hashmap;
 use once_cell::sync::Lazy;
 
 use crate::analysis::UnsafeDataflowBehaviorFlag;
+use crate::config::{user_config, BypassConfig};
 
 /*
 How to find a path for unknown item:
@@ -27,6 +28,13 @@ pub const INTRINSICS_COPY_NONOVERLAPPING: [&str; 3] = ["core", "intrinsics", "co
 pub const VEC_SET_LEN: [&str; 4] = ["alloc", "vec", "Vec", "set_len"];
 pub const VEC_FROM_RAW_PARTS: [&str; 4] = ["alloc", "vec", "Vec", "from_raw_parts"];
 
+pub const MEM_UNINITIALIZED: [&str; 3] = ["core", "mem", "uninitialized"];
+pub const MAYBE_UNINIT_ASSUME_INIT: [&str; 5] =
+    ["core", "mem", "maybe_uninit", "MaybeUninit", "assume_init"];
+pub const MAYBE_UNINIT_UNINIT: [&str; 5] = ["core", "mem", "maybe_uninit", "MaybeUninit", "uninit"];
+pub const MAYBE_UNINIT_UNINIT_ARRAY: [&str; 5] =
+    ["core", "mem", "maybe_uninit", "MaybeUninit", "uninit_array"];
+
 // Weak bypasses
 pub const TRANSMUTE: [&str; 4] = ["core", "intrinsics", "", "transmute"];
 
@@ -43,14 +51,49 @@ pub const SLICE_GET_UNCHECKED_MUT: [&str; 4] = ["core", "slice", "<impl [T]>", "
 
 pub const PTR_SLICE_FROM_RAW_PARTS: [&str; 3] = ["core", "ptr", "slice_from_raw_parts"];
 pub const PTR_SLICE_FROM_RAW_PARTS_MUT: [&str; 3] = ["core", "ptr", "slice_from_raw_parts_mut"];
-pub const SLICE_FROM_RAW_PARTS: [&str; 3] = ["core", "slice", "from_raw_parts"];
-pub const SLICE_FROM_RAW_PARTS_MUT: [&str; 3] = ["core", "slice", "from_raw_parts_mut"];
+// Defined in `core::slice::raw` and re-exported as `core::slice::from_raw_parts`
+pub const SLICE_FROM_RAW_PARTS: [&str; 4] = ["core", "slice", "raw", "from_raw_parts"];
+pub const SLICE_FROM_RAW_PARTS_MUT: [&str; 4] = ["core", "slice", "raw", "from_raw_parts_mut"];
 
 // Generic function call
 pub const PTR_DROP_IN_PLACE: [&str; 3] = ["core", "ptr", "drop_in_place"];
 pub const PTR_DIRECT_DROP_IN_PLACE: [&str; 5] =
     ["core", "ptr", "mut_ptr", "<impl *mut T>", "drop_in_place"];
 
+// Vec methods that neither initialize nor expose the buffer
+pub const VEC_WITH_CAPACITY: [&str; 4] = ["alloc", "vec", "Vec", "with_capacity"];
+pub const VEC_LEN: [&str; 4] = ["alloc", "vec", "Vec", "len"];
+pub const VEC_CAPACITY: [&str; 4] = ["alloc", "vec", "Vec", "capacity"];
+pub const SLICE_LEN: [&str; 4] = ["core", "slice", "<impl [T]>", "len"];
+
+// Functions that clamp an untrusted value
+pub const CMP_MIN: [&str; 3] = ["core", "cmp", "min"];
+pub const ORD_MIN: [&str; 4] = ["core", "cmp", "Ord", "min"];
+
+// The `?` operator
+pub const TRY_BRANCH: [&str; 5] = ["core", "ops", "try_trait", "Try", "branch"];
+
+// I/O traits whose methods write into a caller-provided buffer
+pub const IO_READ: [&str; 3] = ["std", "io", "Read"];
+pub const IO_BUF_READ: [&str; 3] = ["std", "io", "BufRead"];
+
+// Functions that free the memory behind their argument, or take its ownership to free it later
+pub const ALLOC_DEALLOC: [&str; 3] = ["alloc", "alloc", "dealloc"];
+pub const BOX_FROM_RAW: [&str; 4] = ["alloc", "boxed", "Box", "from_raw"];
+pub const RC_FROM_RAW: [&str; 4] = ["alloc", "rc", "Rc", "from_raw"];
+pub const ARC_FROM_RAW: [&str; 4] = ["alloc", "sync", "Arc", "from_raw"];
+pub const STRING_FROM_RAW_PARTS: [&str; 4] = ["alloc", "string", "String", "from_raw_parts"];
+pub const CSTRING_FROM_RAW: [&str; 5] = ["std", "ffi", "c_str", "CString", "from_raw"];
+
+pub const NON_NULL: [&str; 4] = ["core", "ptr", "non_null", "NonNull"];
+
+pub const MEM_REPLACE: [&str; 3] = ["core", "mem", "replace"];
+
+// Ownership consumers
+pub const MEM_FORGET: [&str; 3] = ["core", "mem", "forget"];
+pub const MEM_DROP: [&str; 3] = ["core", "mem", "drop"];
+pub const MANUALLY_DROP_NEW: [&str; 5] = ["core", "mem", "manually_drop", "ManuallyDrop", "new"];
+
 pub struct PathSet {
     set: HashSet<Vec<Symbol>>,
 }
@@ -69,6 +112,22 @@ impl PathSet {
     pub fn contains(&self, target: &Vec<Symbol>) -> bool {
         self.set.contains(target)
     }
+
+    pub fn extend<I>(&mut self, paths: I)
+    where
+        I: IntoIterator<Item = Vec<Symbol>>,
+    {
+        self.set.extend(paths)
+    }
+}
+
+/// Parses a `::` separated def path (e.g., `core::ptr::read`).
+pub fn parse_path(path: &str) -> Vec<Symbol> {
+    path.split("::").map(|p| Symbol::intern(p.trim())).collect()
+}
+
+fn user_bypass_paths(bypasses: &[BypassConfig]) -> impl Iterator<Item = Vec<Symbol>> + '_ {
+    bypasses.iter().map(|bypass| parse_path(&bypass.path))
 }
 
 /// Special path used only for path discovery
@@ -76,7 +135,7 @@ pub static SPECIAL_PATH_DISCOVERY: Lazy<PathSet> =
     Lazy::new(move || PathSet::new(&[&["rudra_paths_discovery", "PathsDiscovery", "discover"]]));
 
 pub static STRONG_LIFETIME_BYPASS_LIST: Lazy<PathSet> = Lazy::new(move || {
-    PathSet::new(&[
+    let mut set = PathSet::new(&[
         &PTR_READ,
         &PTR_DIRECT_READ,
         //
@@ -85,11 +144,18 @@ pub static STRONG_LIFETIME_BYPASS_LIST: Lazy<PathSet> = Lazy::new(move || {
         //
         &VEC_SET_LEN,
         &VEC_FROM_RAW_PARTS,
-    ])
+        //
+        &MEM_UNINITIALIZED,
+        &MAYBE_UNINIT_ASSUME_INIT,
+    ]);
+    set.extend(user_bypass_paths(
+        &user_config().unsafe_dataflow.strong_bypass,
+    ));
+    set
 });
 
 pub static WEAK_LIFETIME_BYPASS_LIST: Lazy<PathSet> = Lazy::new(move || {
-    PathSet::new(&[
+    let mut set = PathSet::new(&[
         &TRANSMUTE,
         //
         &PTR_WRITE,
@@ -107,18 +173,65 @@ pub static WEAK_LIFETIME_BYPASS_LIST: Lazy<PathSet> = Lazy::new(move || {
         &PTR_SLICE_FROM_RAW_PARTS_MUT,
         &SLICE_FROM_RAW_PARTS,
         &SLICE_FROM_RAW_PARTS_MUT,
+    ]);
+    set.extend(user_bypass_paths(
+        &user_config().unsafe_dataflow.weak_bypass,
+    ));
+    set
+});
+
+pub static GENERIC_FN_LIST: Lazy<PathSet> = Lazy::new(move || {
+    let mut set = PathSet::new(&[&PTR_DROP_IN_PLACE, &PTR_DIRECT_DROP_IN_PLACE]);
+    set.extend(
+        user_config()
+            .unsafe_dataflow
+            .sink
+            .iter()
+            .map(|path| parse_path(path)),
+    );
+    set
+});
+
+/// Functions that free the memory behind their pointer argument
+pub static FREE_FN_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &ALLOC_DEALLOC,
+        //
+        &BOX_FROM_RAW,
+        &RC_FROM_RAW,
+        &ARC_FROM_RAW,
+        &VEC_FROM_RAW_PARTS,
+        &STRING_FROM_RAW_PARTS,
+        &CSTRING_FROM_RAW,
     ])
 });
 
-pub static GENERIC_FN_LIST: Lazy<PathSet> =
-    Lazy::new(move || PathSet::new(&[&PTR_DROP_IN_PLACE, &PTR_DIRECT_DROP_IN_PLACE]));
+/// Functions that take the ownership of their argument without returning it
+pub static OWNERSHIP_CONSUMER_LIST: Lazy<PathSet> = Lazy::new(move || {
+    PathSet::new(&[
+        &MEM_FORGET,
+        &MEM_DROP,
+        &MANUALLY_DROP_NEW,
+        //
+        &PTR_WRITE,
+        &PTR_DIRECT_WRITE,
+    ])
+});
 
 type PathMap = HashMap<Vec<Symbol>, UnsafeDataflowBehaviorFlag>;
 
+fn user_bypass_map(
+    bypasses: &[BypassConfig],
+) -> impl Iterator<Item = (Vec<Symbol>, UnsafeDataflowBehaviorFlag)> + '_ {
+    bypasses
+        .iter()
+        .map(|bypass| (parse_path(&bypass.path), bypass.behavior_flag()))
+}
+
 pub static STRONG_BYPASS_MAP: Lazy<PathMap> = Lazy::new(move || {
     use UnsafeDataflowBehaviorFlag as BehaviorFlag;
 
-    hashmap! {
+    let mut map = hashmap! {
         PTR_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::READ_FLOW,
         PTR_DIRECT_READ.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::READ_FLOW,
         //
@@ -128,13 +241,20 @@ pub static STRONG_BYPASS_MAP: Lazy<PathMap> = Lazy::new(move || {
         VEC_SET_LEN.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::VEC_SET_LEN,
         //
         VEC_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::VEC_FROM_RAW,
-    }
+        //
+        MEM_UNINITIALIZED.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::UNINIT,
+        MAYBE_UNINIT_ASSUME_INIT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::UNINIT,
+    };
+    map.extend(user_bypass_map(
+        &user_config().unsafe_dataflow.strong_bypass,
+    ));
+    map
 });
 
 pub static WEAK_BYPASS_MAP: Lazy<PathMap> = Lazy::new(move || {
     use UnsafeDataflowBehaviorFlag as BehaviorFlag;
 
-    hashmap! {
+    let mut map = hashmap! {
         TRANSMUTE.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::TRANSMUTE,
         //
         PTR_WRITE.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::WRITE_FLOW,
@@ -152,5 +272,22 @@ pub static WEAK_BYPASS_MAP: Lazy<PathMap> = Lazy::new(move || {
         PTR_SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::SLICE_FROM_RAW,
         SLICE_FROM_RAW_PARTS.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::SLICE_FROM_RAW,
         SLICE_FROM_RAW_PARTS_MUT.iter().map(|p| Symbol::intern(p)).collect::<Vec<_>>() => BehaviorFlag::SLICE_FROM_RAW,
-    }
+    };
+    map.extend(user_bypass_map(&user_config().unsafe_dataflow.weak_bypass));
+    map
+});
+
+/// User category names of the bypasses defined in the user config
+pub static USER_BYPASS_CATEGORY_MAP: Lazy<HashMap<Vec<Symbol>, String>> = Lazy::new(move || {
+    let unsafe_dataflow = &user_config().unsafe_dataflow;
+    unsafe_dataflow
+        .strong_bypass
+        .iter()
+        .chain(unsafe_dataflow.weak_bypass.iter())
+        .filter_map(|bypass| {
+            bypass
+                .user_category()
+                .map(|category| (parse_path(&bypass.path), category.to_owned()))
+        })
+        .collect()
 });
@@ -1,5 +1,6 @@
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
 
 use std::borrow::Cow;
 use std::env;
@@ -14,6 +15,14 @@ use serde::Serialize;
 
 use crate::utils;
 
+pub mod baseline;
+mod json;
+mod sarif;
+
+pub use baseline::BaselineLogger;
+pub use json::JsonLinesLogger;
+pub use sarif::SarifLogger;
+
 static REPORT_LOGGER: OnceCell<Box<dyn ReportLogger>> = OnceCell::new();
 
 /// Flushes the global report logger when dropped.
@@ -40,9 +49,41 @@ pub fn init_report_logger(report_logger: Box<dyn ReportLogger>) -> FlushHandle {
 }
 
 pub fn default_report_logger() -> Box<dyn ReportLogger> {
-    match env::var_os("RUDRA_REPORT_PATH") {
-        Some(val) => Box::new(FileLogger::new(val)),
-        None => Box::new(StderrLogger::new()),
+    let logger = format_report_logger();
+    let baseline = env::var_os("RUDRA_BASELINE").map(PathBuf::from);
+    let fingerprint_dir = env::var_os("RUDRA_FINGERPRINT_DIR").map(PathBuf::from);
+    if baseline.is_some() || fingerprint_dir.is_some() {
+        Box::new(BaselineLogger::new(logger, baseline, fingerprint_dir))
+    } else {
+        logger
+    }
+}
+
+fn format_report_logger() -> Box<dyn ReportLogger> {
+    let report_path = env::var_os("RUDRA_REPORT_PATH");
+    match env::var("RUDRA_REPORT_FORMAT").as_deref() {
+        Ok("json") => Box::new(JsonLinesLogger::new(report_path.map(PathBuf::from))),
+        Ok("sarif") => Box::new(SarifLogger::new(report_path.map(PathBuf::from))),
+        Ok("toml") | Err(_) => match report_path {
+            Some(val) => Box::new(FileLogger::new(val)),
+            None => Box::new(StderrLogger::new()),
+        },
+        Ok(format) => {
+            eprintln!(
+                "Rudra failed to set up the report logger: unknown RUDRA_REPORT_FORMAT `{}`",
+                format
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Splits an analyzer string (`UnsafeDestructor:/MovedRead/DoubleDrop`)
+/// into the analyzer name and its behavior flags.
+fn split_analyzer(analyzer: &str) -> (&str, Vec<&str>) {
+    match analyzer.split_once(":/") {
+        Some((name, behaviors)) => (name, behaviors.split('/').collect()),
+        None => (analyzer.trim_end_matches(':'), Vec::new()),
     }
 }
 
@@ -66,13 +107,188 @@ impl fmt::Display for ReportLevel {
     }
 }
 
+/// What a highlighted sub-span of a report is
+#[derive(Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanRole {
+    /// A lifetime bypass that can break an invariant when generic code panics
+    StrongBypass,
+    /// A lifetime bypass that is harmful only in limited cases
+    WeakBypass,
+    /// A call to generic code that cannot be resolved in the analyzed crate
+    UnresolvableGenericCall,
+    /// A call through a trait object or a function pointer
+    DynamicCall,
+    /// A guard that restores the invariant when it is dropped
+    DropGuard,
+    /// A drop on the unwind path of a panic
+    UnwindDrop,
+    /// A place that is invalidated (moved out, uninitialized)
+    Invalidation,
+    /// A use of an invalidated or dangling value
+    Use,
+    /// Creation of an uninitialized buffer
+    UninitBuffer,
+    /// A value returned by user code that is trusted by unsafe code
+    UntrustedValue,
+    /// Unsafe code that relies on an untrusted value
+    Sink,
+}
+
+impl SpanRole {
+    /// Color of the sub-span in the colored source
+    pub fn color(&self) -> termcolor::Color {
+        use termcolor::Color;
+        match self {
+            SpanRole::StrongBypass
+            | SpanRole::Invalidation
+            | SpanRole::UninitBuffer
+            | SpanRole::Sink => Color::Red,
+            SpanRole::WeakBypass | SpanRole::Use | SpanRole::UntrustedValue => Color::Yellow,
+            SpanRole::UnresolvableGenericCall => Color::Cyan,
+            SpanRole::DynamicCall => Color::Magenta,
+            SpanRole::DropGuard => Color::Green,
+            SpanRole::UnwindDrop => Color::Blue,
+        }
+    }
+
+    /// Human readable name of the role
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpanRole::StrongBypass => "strong bypass",
+            SpanRole::WeakBypass => "weak bypass",
+            SpanRole::UnresolvableGenericCall => "unresolvable generic call",
+            SpanRole::DynamicCall => "dynamic call",
+            SpanRole::DropGuard => "drop guard",
+            SpanRole::UnwindDrop => "drop on unwind path",
+            SpanRole::Invalidation => "invalidation",
+            SpanRole::Use => "use",
+            SpanRole::UninitBuffer => "uninitialized buffer",
+            SpanRole::UntrustedValue => "untrusted value",
+            SpanRole::Sink => "sink",
+        }
+    }
+}
+
+/// Identifies a report across runs, independent of line numbers and formatting.
+/// It is a hash of the analyzer name (without behavior flags), the crate-qualified def path of the item,
+/// and the source code of the item with whitespace normalized.
+fn fingerprint(tcx: TyCtxt<'_>, analyzer: &str, item_did: DefId, source: &str) -> String {
+    // 64-bit FNV-1a, which is stable across Rust versions unlike `DefaultHasher`
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let (analyzer, _) = split_analyzer(analyzer);
+    let item = format!(
+        "{}::{}",
+        tcx.crate_name(item_did.krate),
+        tcx.def_path_str(item_did)
+    );
+    let source = source.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in [analyzer, &item, &source] {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
 #[derive(Serialize)]
 pub struct Report {
     level: ReportLevel,
     analyzer: Cow<'static, str>,
+    fingerprint: String,
     description: Cow<'static, str>,
     location: String,
     source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<Suggestion>,
+    /// Def path of the reported item
+    #[serde(skip)]
+    item: String,
+    /// Resolved location of the main span, for loggers that need exact positions
+    #[serde(skip)]
+    region: SourceRegion,
+    /// Highlighted sub-spans of the main span and their roles
+    #[serde(skip)]
+    sub_regions: Vec<(SpanRole, SourceRegion)>,
+}
+
+/// A suggested fix, both as the rewritten source code and as edits that a tool can apply
+#[derive(Serialize)]
+pub struct Suggestion {
+    message: String,
+    /// The rewritten source code, for users
+    rewritten: String,
+    edits: Vec<SourceEdit>,
+}
+
+impl Suggestion {
+    pub fn new<T>(message: T, rewritten: String, edits: Vec<SourceEdit>) -> Self
+    where
+        T: Into<String>,
+    {
+        Suggestion {
+            message: message.into(),
+            rewritten,
+            edits,
+        }
+    }
+}
+
+/// A span resolved to its file.
+/// Lines and columns are 1-based as in `location`, and bytes are offsets within the file.
+#[derive(Serialize, Clone)]
+pub struct SourceRegion {
+    file: String,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+    byte_start: usize,
+    byte_end: usize,
+}
+
+impl SourceRegion {
+    pub fn new(tcx: TyCtxt<'_>, span: Span) -> Self {
+        let source_map = tcx.sess.source_map();
+        let start = source_map.lookup_byte_offset(span.lo());
+        let end = source_map.lookup_byte_offset(span.hi());
+        let start_loc = source_map.lookup_char_pos(span.lo());
+        let end_loc = source_map.lookup_char_pos(span.hi());
+
+        SourceRegion {
+            file: source_map
+                .filename_for_diagnostics(&start.sf.name)
+                .to_string(),
+            line_start: start_loc.line,
+            column_start: start_loc.col.0 + 1,
+            line_end: end_loc.line,
+            column_end: end_loc.col.0 + 1,
+            byte_start: start.pos.0 as usize,
+            byte_end: end.pos.0 as usize,
+        }
+    }
+}
+
+/// Replaces the source code within a span.
+#[derive(Serialize)]
+pub struct SourceEdit {
+    #[serde(flatten)]
+    region: SourceRegion,
+    replacement: String,
+}
+
+impl SourceEdit {
+    pub fn new(tcx: TyCtxt<'_>, span: Span, replacement: String) -> Self {
+        SourceEdit {
+            region: SourceRegion::new(tcx, span),
+            replacement,
+        }
+    }
 }
 
 impl Report {
@@ -101,13 +317,20 @@ impl Report {
                 .unwrap_or_else(|e| format!("unable to get source: {:?}", e))
         };
         let location = source_map.span_to_diagnostic_string(span);
+        let analyzer = analyzer.into();
+        let item_did = hir_map.local_def_id(item_hir_id).to_def_id();
 
         Report {
             level,
-            analyzer: analyzer.into(),
+            fingerprint: fingerprint(tcx, &analyzer, item_did, &source),
+            analyzer,
             description: description.into(),
             location,
             source,
+            suggestion: None,
+            item: tcx.def_path_str(item_did),
+            region: SourceRegion::new(tcx, span),
+            sub_regions: Vec::new(),
         }
     }
 
@@ -116,6 +339,7 @@ impl Report {
         level: ReportLevel,
         analyzer: T,
         description: U,
+        item_did: DefId,
         color_span: &utils::ColorSpan,
     ) -> Report
     where
@@ -124,15 +348,49 @@ impl Report {
     {
         let source_map = tcx.sess.source_map();
         let location = source_map.span_to_diagnostic_string(color_span.main_span());
+        let analyzer = analyzer.into();
+        let plain_source = source_map
+            .span_to_snippet(color_span.main_span())
+            .unwrap_or_default();
 
         Report {
             level,
-            analyzer: analyzer.into(),
+            fingerprint: fingerprint(tcx, &analyzer, item_did, &plain_source),
+            analyzer,
             description: description.into(),
             location,
             source: color_span.to_colored_string(),
+            suggestion: None,
+            item: tcx.def_path_str(item_did),
+            region: SourceRegion::new(tcx, color_span.main_span()),
+            sub_regions: color_span
+                .sub_spans()
+                .iter()
+                .map(|&(role, span)| (role, SourceRegion::new(tcx, span)))
+                .collect(),
         }
     }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    pub fn analyzer(&self) -> &str {
+        &self.analyzer
+    }
+
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Report {
+        self.suggestion = Some(suggestion);
+        self
+    }
 }
 
 pub trait ReportLogger: Sync + Send {
@@ -173,6 +431,15 @@ impl ReportLogger for StderrLogger {
                 &report.source
             )
             .expect("stderr closed");
+
+            if let Some(suggestion) = &report.suggestion {
+                writeln!(
+                    &mut handle,
+                    "help: {}\n{}",
+                    &suggestion.message, &suggestion.rewritten
+                )
+                .expect("stderr closed");
+            }
         }
     }
 }
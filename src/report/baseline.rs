@@ -0,0 +1,174 @@
+//! Baseline of known reports
+//!
+//! `cargo rudra --baseline <path>` records the reports of the run in the baseline file if it doesn't exist,
+//! and later runs only output reports whose fingerprints are not in the baseline.
+//! Each `rudra` process writes the reports it has seen to `RUDRA_FINGERPRINT_DIR`,
+//! and `cargo rudra` merges them into the baseline file after all targets are analyzed.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::{Report, ReportLogger};
+use crate::progress_info;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// A known report. Only the fingerprint is used for matching,
+/// and the other fields help users to triage the baseline file.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub analyzer: String,
+    pub item: String,
+    pub description: String,
+}
+
+impl BaselineEntry {
+    fn new(report: &Report) -> Self {
+        BaselineEntry {
+            fingerprint: report.fingerprint().to_owned(),
+            analyzer: report.analyzer().to_owned(),
+            item: report.item().to_owned(),
+            description: report.description().to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    reports: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn new(mut reports: Vec<BaselineEntry>) -> Self {
+        reports.sort();
+        reports.dedup_by(|a, b| a.fingerprint == b.fingerprint);
+        Baseline {
+            version: BASELINE_VERSION,
+            reports,
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let baseline: Baseline = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported baseline version {}", baseline.version),
+            ));
+        }
+        Ok(baseline)
+    }
+
+    /// Merges the reports that `rudra` processes wrote to `RUDRA_FINGERPRINT_DIR`
+    pub fn load_fingerprint_dir(dir: &Path) -> io::Result<Self> {
+        let mut reports = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "json") {
+                let entries: Vec<BaselineEntry> =
+                    serde_json::from_str(&fs::read_to_string(&path)?)?;
+                reports.extend(entries);
+            }
+        }
+        Ok(Baseline::new(reports))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    fn fingerprints(&self) -> HashSet<String> {
+        self.reports
+            .iter()
+            .map(|entry| entry.fingerprint.clone())
+            .collect()
+    }
+}
+
+/// Drops the reports in the baseline, and records all reports to `fingerprint_dir`.
+pub struct BaselineLogger {
+    inner: Box<dyn ReportLogger>,
+    known: HashSet<String>,
+    suppressed: AtomicUsize,
+    fingerprint_dir: Option<PathBuf>,
+    seen: Mutex<Vec<BaselineEntry>>,
+}
+
+impl BaselineLogger {
+    pub fn new(
+        inner: Box<dyn ReportLogger>,
+        baseline: Option<PathBuf>,
+        fingerprint_dir: Option<PathBuf>,
+    ) -> Self {
+        let known = match baseline {
+            Some(path) => Baseline::load(&path)
+                .unwrap_or_else(|e| panic!("cannot read Rudra baseline {}: {}", path.display(), e))
+                .fingerprints(),
+            None => HashSet::new(),
+        };
+
+        BaselineLogger {
+            inner,
+            known,
+            suppressed: AtomicUsize::new(0),
+            fingerprint_dir,
+            seen: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl ReportLogger for BaselineLogger {
+    fn log(&self, report: Report) {
+        if self.fingerprint_dir.is_some() {
+            self.seen.lock().push(BaselineEntry::new(&report));
+        }
+
+        if self.known.contains(report.fingerprint()) {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.inner.log(report);
+        }
+    }
+
+    fn flush(&self) {
+        let suppressed = self.suppressed.load(Ordering::Relaxed);
+        if suppressed > 0 {
+            progress_info!(
+                "{} known reports are suppressed by the baseline",
+                suppressed
+            );
+        }
+
+        if let Some(dir) = &self.fingerprint_dir {
+            // Several `rudra` processes write to the same directory
+            let nanos = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos());
+            let path = dir.join(format!("{}-{}.json", std::process::id(), nanos));
+            fs::write(
+                path,
+                serde_json::to_string(&*self.seen.lock()).expect("failed to serialize baseline"),
+            )
+            .expect("cannot write Rudra fingerprints to file");
+        }
+
+        self.inner.flush();
+    }
+}
@@ -0,0 +1,105 @@
+//! JSON Lines output with explicit span data
+//!
+//! Each line is a report with the analyzer name, its behavior flags, the def path of the reported item,
+//! and the resolved main span and sub-spans, so that tools don't need to parse `location` and `source`.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use super::{
+    split_analyzer, Report, ReportLevel, ReportLogger, SourceRegion, SpanRole, Suggestion,
+};
+
+pub struct JsonLinesLogger {
+    reports: Mutex<Vec<Report>>,
+    /// The reports are printed to stdout if `None`
+    file_path: Option<PathBuf>,
+}
+
+impl JsonLinesLogger {
+    pub fn new(file_path: Option<PathBuf>) -> Self {
+        JsonLinesLogger {
+            reports: Mutex::new(Vec::new()),
+            file_path,
+        }
+    }
+}
+
+impl ReportLogger for JsonLinesLogger {
+    fn log(&self, report: Report) {
+        self.reports.lock().push(report);
+    }
+
+    fn flush(&self) {
+        let reports = self.reports.lock();
+        let mut lines = String::new();
+        for report in reports.iter() {
+            lines.push_str(
+                &serde_json::to_string(&JsonReport::new(report))
+                    .expect("failed to serialize Rudra report"),
+            );
+            lines.push('\n');
+        }
+
+        match &self.file_path {
+            Some(file_path) => {
+                fs::write(file_path, lines).expect("cannot write Rudra report to file");
+            }
+            None => {
+                let stdout = std::io::stdout();
+                write!(&mut stdout.lock(), "{}", lines).expect("stdout closed");
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    level: ReportLevel,
+    analyzer: &'a str,
+    behaviors: Vec<&'a str>,
+    item: &'a str,
+    fingerprint: &'a str,
+    description: &'a str,
+    /// `file`, `line_start`, `column_start`, `line_end`, `column_end`, `byte_start`, and `byte_end`
+    #[serde(flatten)]
+    region: &'a SourceRegion,
+    sub_spans: Vec<JsonSubSpan<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<&'a Suggestion>,
+}
+
+impl<'a> JsonReport<'a> {
+    fn new(report: &'a Report) -> Self {
+        let (analyzer, behaviors) = split_analyzer(&report.analyzer);
+        JsonReport {
+            level: report.level,
+            analyzer,
+            behaviors,
+            item: &report.item,
+            fingerprint: &report.fingerprint,
+            description: &report.description,
+            region: &report.region,
+            sub_spans: report
+                .sub_regions
+                .iter()
+                .map(|(role, region)| JsonSubSpan {
+                    role: *role,
+                    region,
+                })
+                .collect(),
+            suggestion: report.suggestion.as_ref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSubSpan<'a> {
+    role: SpanRole,
+    #[serde(flatten)]
+    region: &'a SourceRegion,
+}
@@ -0,0 +1,401 @@
+//! SARIF 2.1.0 output for code scanning tools
+//!
+//! Each report becomes a result whose rule ID is `<analyzer>/<behavior>` (e.g., `UnsafeDestructor/MovedRead`),
+//! or the analyzer name for analyzers without behavior flags.
+//! A report with several behavior flags uses the first one as its rule and lists all of them in `properties`.
+//!
+//! The log covers a single rustc invocation. Under `cargo rudra`, each crate writes its own log
+//! (`RUDRA_REPORT_PATH` gets the per-crate suffix), and without a report path
+//! one SARIF document per crate is printed to stdout.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use termcolor::Color;
+
+use super::{split_analyzer, Report, ReportLevel, ReportLogger, SourceRegion, SpanRole};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const RUDRA_URI: &str = "https://github.com/sslab-gatech/Rudra";
+
+pub struct SarifLogger {
+    reports: Mutex<Vec<Report>>,
+    /// The log is printed to stdout if `None`
+    file_path: Option<PathBuf>,
+}
+
+impl SarifLogger {
+    pub fn new(file_path: Option<PathBuf>) -> Self {
+        SarifLogger {
+            reports: Mutex::new(Vec::new()),
+            file_path,
+        }
+    }
+}
+
+impl ReportLogger for SarifLogger {
+    fn log(&self, report: Report) {
+        self.reports.lock().push(report);
+    }
+
+    fn flush(&self) {
+        let reports = self.reports.lock();
+        // Unlike the TOML report, an empty log is written so that dashboards can tell a clean run
+        let log = serde_json::to_string_pretty(&SarifLog::new(&reports))
+            .expect("failed to serialize Rudra report");
+
+        match &self.file_path {
+            Some(file_path) => {
+                fs::write(file_path, log).expect("cannot write Rudra report to file");
+            }
+            None => {
+                let stdout = std::io::stdout();
+                writeln!(&mut stdout.lock(), "{}", log).expect("stdout closed");
+            }
+        }
+    }
+}
+
+/// Splits `UnsafeDestructor:/MovedRead/DoubleDrop` into rule IDs
+/// `UnsafeDestructor/MovedRead` and `UnsafeDestructor/DoubleDrop`.
+fn rule_ids(analyzer: &str) -> Vec<String> {
+    let (name, behaviors) = split_analyzer(analyzer);
+    if behaviors.is_empty() {
+        vec![name.to_owned()]
+    } else {
+        behaviors
+            .iter()
+            .map(|behavior| format!("{}/{}", name, behavior))
+            .collect()
+    }
+}
+
+fn level(level: ReportLevel) -> &'static str {
+    match level {
+        ReportLevel::Error => "error",
+        ReportLevel::Warning => "warning",
+        ReportLevel::Info => "note",
+    }
+}
+
+fn color_name(color: Color) -> String {
+    format!("{:?}", color).to_lowercase()
+}
+
+/// Absolute paths become `file://` URIs, and relative paths stay relative to the working directory.
+fn artifact_uri(file: &str) -> String {
+    let encoded = file
+        .replace('%', "%25")
+        .replace(' ', "%20")
+        .replace('\\', "/");
+    if Path::new(file).is_absolute() {
+        if encoded.starts_with('/') {
+            format!("file://{}", encoded)
+        } else {
+            format!("file:///{}", encoded)
+        }
+    } else {
+        encoded
+    }
+}
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+impl SarifLog {
+    fn new(reports: &[Report]) -> Self {
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut results = Vec::new();
+
+        for report in reports {
+            let report_rules = rule_ids(&report.analyzer);
+            let mut rule_indices = Vec::new();
+            for rule_id in report_rules.iter() {
+                let index = match rules.iter().position(|rule| &rule.id == rule_id) {
+                    Some(index) => index,
+                    None => {
+                        rules.push(Rule::new(rule_id));
+                        rules.len() - 1
+                    }
+                };
+                rule_indices.push(index);
+            }
+            results.push(SarifResult::new(report, report_rules, rule_indices[0]));
+        }
+
+        SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "Rudra",
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: RUDRA_URI,
+                        rules,
+                    },
+                },
+                // rustc counts columns in characters
+                column_kind: "unicodeCodePoints",
+                results,
+            }],
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    name: String,
+    short_description: Message,
+}
+
+impl Rule {
+    fn new(id: &str) -> Self {
+        let (analyzer, behavior) = id.split_once('/').unwrap_or((id, id));
+        Rule {
+            id: id.to_owned(),
+            name: behavior.to_owned(),
+            short_description: Message::new(if analyzer == behavior {
+                format!("{} report", analyzer)
+            } else {
+                format!("{} report with the {} behavior", analyzer, behavior)
+            }),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+impl Message {
+    fn new<T: Into<String>>(text: T) -> Self {
+        Message { text: text.into() }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+    partial_fingerprints: PartialFingerprints,
+    properties: ResultProperties,
+}
+
+#[derive(Serialize)]
+struct PartialFingerprints {
+    #[serde(rename = "rudraFingerprint/v1")]
+    rudra: String,
+}
+
+impl SarifResult {
+    fn new(report: &Report, rules: Vec<String>, rule_index: usize) -> Self {
+        let related_locations = report
+            .sub_regions
+            .iter()
+            .enumerate()
+            .map(|(id, (role, region))| {
+                let mut location = Location::new(region);
+                location.id = Some(id);
+                location.message = Some(Message::new(role.name()));
+                location.properties = Some(LocationProperties {
+                    role: *role,
+                    color: color_name(role.color()),
+                });
+                location
+            })
+            .collect();
+
+        let fixes = report
+            .suggestion
+            .iter()
+            .map(|suggestion| Fix {
+                description: Message::new(suggestion.message.clone()),
+                artifact_changes: suggestion
+                    .edits
+                    .iter()
+                    .map(|edit| ArtifactChange {
+                        artifact_location: ArtifactLocation::new(&edit.region),
+                        replacements: vec![Replacement {
+                            deleted_region: Region::new(&edit.region),
+                            inserted_content: Content {
+                                text: edit.replacement.clone(),
+                            },
+                        }],
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        SarifResult {
+            rule_id: rules[0].clone(),
+            rule_index,
+            level: level(report.level),
+            message: Message::new(report.description.clone()),
+            locations: vec![Location::new(&report.region)],
+            related_locations,
+            fixes,
+            partial_fingerprints: PartialFingerprints {
+                rudra: report.fingerprint.clone(),
+            },
+            properties: ResultProperties {
+                analyzer: report.analyzer.to_string(),
+                rules,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ResultProperties {
+    /// The analyzer string of the report, as printed by the other loggers
+    analyzer: String,
+    /// All behaviors of the report
+    rules: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<LocationProperties>,
+}
+
+impl Location {
+    fn new(region: &SourceRegion) -> Self {
+        Location {
+            id: None,
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation::new(region),
+                region: Region::new(region),
+            },
+            message: None,
+            properties: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LocationProperties {
+    role: SpanRole,
+    /// Color of the sub-span in the other loggers
+    color: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+impl ArtifactLocation {
+    fn new(region: &SourceRegion) -> Self {
+        ArtifactLocation {
+            uri: artifact_uri(&region.file),
+        }
+    }
+}
+
+/// Lines and columns are 1-based and the end column is exclusive, same as `SourceRegion`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: usize,
+    byte_length: usize,
+}
+
+impl Region {
+    fn new(region: &SourceRegion) -> Self {
+        Region {
+            start_line: region.line_start,
+            start_column: region.column_start,
+            end_line: region.line_end,
+            end_column: region.column_end,
+            byte_offset: region.byte_start,
+            byte_length: region.byte_end - region.byte_start,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Content,
+}
+
+#[derive(Serialize)]
+struct Content {
+    text: String,
+}
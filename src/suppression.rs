@@ -0,0 +1,154 @@
+//! `rudra::allow` attributes that mark items as reviewed
+//!
+//! `#[cfg_attr(rudra, rudra::allow(unsafe_dataflow, send_sync_variance))]` suppresses the reports of the listed
+//! analyzers on the item and everything nested in it. `all` suppresses every analyzer.
+//! Rudra registers the `rudra` tool with `-Zcrate-attr`, and `--cfg=rudra` keeps the attribute out of normal builds.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use rustc_ast::{AttrKind, Attribute};
+use rustc_hir::{def_id::DefId, HirId};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::report::{rudra_report, Report, ReportLevel};
+use crate::utils;
+
+/// Names of the analyzers that can be suppressed
+pub const ANALYZER_NAMES: &[&str] = &[
+    "unsafe_destructor",
+    "send_sync_variance",
+    "unsafe_dataflow",
+    "uninit_exposure",
+    "higher_order_invariant",
+];
+
+const ALL: &str = "all";
+
+struct Suppression {
+    analyzer: String,
+    span: Span,
+    /// The item that has the attribute
+    owner: DefId,
+    used: Cell<bool>,
+}
+
+pub struct Suppressions {
+    suppressions: Vec<Suppression>,
+    /// Maps a HIR node to the suppressions in its attributes
+    by_node: HashMap<HirId, Vec<usize>>,
+}
+
+impl Suppressions {
+    pub fn collect(tcx: TyCtxt<'_>) -> Self {
+        let mut suppressions = Vec::new();
+        let mut by_node: HashMap<HirId, Vec<usize>> = HashMap::new();
+
+        for (owner, owner_info) in tcx.hir().krate().owners.iter_enumerated() {
+            let owner_info = match owner_info {
+                Some(owner_info) => owner_info,
+                None => continue,
+            };
+            for (&local_id, attrs) in owner_info.attrs.map.iter() {
+                let hir_id = HirId { owner, local_id };
+                for (analyzer, span) in attrs.iter().flat_map(allowed_analyzers) {
+                    by_node.entry(hir_id).or_default().push(suppressions.len());
+                    suppressions.push(Suppression {
+                        analyzer,
+                        span,
+                        owner: owner.to_def_id(),
+                        used: Cell::new(false),
+                    });
+                }
+            }
+        }
+
+        Suppressions {
+            suppressions,
+            by_node,
+        }
+    }
+
+    /// Returns true if `analyzer` is allowed on the item or one of its parents,
+    /// and marks the matching suppressions as used.
+    pub fn is_suppressed(&self, tcx: TyCtxt<'_>, analyzer: &str, item: DefId) -> bool {
+        let hir_map = tcx.hir();
+        let hir_id = match item.as_local() {
+            Some(local_did) => hir_map.local_def_id_to_hir_id(local_did),
+            None => return false,
+        };
+
+        let mut suppressed = false;
+        let ancestors =
+            std::iter::once(hir_id).chain(hir_map.parent_iter(hir_id).map(|(id, _)| id));
+        for id in ancestors {
+            for &idx in self.by_node.get(&id).into_iter().flatten() {
+                let suppression = &self.suppressions[idx];
+                if suppression.analyzer == analyzer || suppression.analyzer == ALL {
+                    suppression.used.set(true);
+                    suppressed = true;
+                }
+            }
+        }
+        suppressed
+    }
+
+    /// Reports suppressions that didn't suppress any report of the enabled analyzers,
+    /// and suppressions of unknown analyzers.
+    pub fn report_unused(&self, tcx: TyCtxt<'_>, enabled_analyzers: &[&str]) {
+        for suppression in self.suppressions.iter() {
+            let analyzer = suppression.analyzer.as_str();
+            let description = if analyzer != ALL && !ANALYZER_NAMES.contains(&analyzer) {
+                format!(
+                    "Unknown analyzer `{}` in `rudra::allow` (expected one of `{}`, `{}`)",
+                    analyzer,
+                    ANALYZER_NAMES.join("`, `"),
+                    ALL
+                )
+            } else if suppression.used.get()
+                || (analyzer != ALL && !enabled_analyzers.contains(&analyzer))
+                || enabled_analyzers.is_empty()
+            {
+                continue;
+            } else {
+                format!("`rudra::allow({})` doesn't suppress any report", analyzer)
+            };
+
+            let color_span = match utils::ColorSpan::new(tcx, suppression.span) {
+                Some(color_span) => color_span,
+                None => continue,
+            };
+            rudra_report(Report::with_color_span(
+                tcx,
+                ReportLevel::Warning,
+                "UnusedSuppression",
+                description,
+                suppression.owner,
+                &color_span,
+            ));
+        }
+    }
+}
+
+/// Analyzer names and their spans in `rudra::allow(...)`
+fn allowed_analyzers(attr: &Attribute) -> Vec<(String, Span)> {
+    let is_rudra_allow = match &attr.kind {
+        AttrKind::Normal(item, _) => {
+            let segments = &item.path.segments;
+            segments.len() == 2
+                && segments[0].ident.as_str() == "rudra"
+                && segments[1].ident.as_str() == "allow"
+        }
+        AttrKind::DocComment(..) => false,
+    };
+    if !is_rudra_allow {
+        return Vec::new();
+    }
+
+    attr.meta_item_list()
+        .unwrap_or_default()
+        .iter()
+        .map(|nested| (nested.name_or_empty().to_string(), nested.span()))
+        .collect()
+}
@@ -8,6 +8,7 @@ use rustc_span::{CharPos, Span};
 use termcolor::{Buffer, Color, ColorSpec, WriteColor};
 
 use crate::compile_time_sysroot;
+use crate::report::SpanRole;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 struct ColorEventId(usize);
@@ -61,6 +62,7 @@ pub struct ColorSpan<'tcx> {
     main_span_end: rustc_span::Loc,
     id_counter: usize,
     sub_span_events: Vec<ColorEvent>,
+    sub_spans: Vec<(SpanRole, Span)>,
 }
 
 impl PartialEq for ColorEvent {
@@ -113,6 +115,7 @@ impl<'tcx> ColorSpan<'tcx> {
                 main_span_end,
                 id_counter: 0,
                 sub_span_events: Vec::new(),
+                sub_spans: Vec::new(),
             })
         } else {
             None
@@ -123,8 +126,14 @@ impl<'tcx> ColorSpan<'tcx> {
         self.main_span
     }
 
+    /// Sub-spans that are successfully added, in the order of addition
+    pub fn sub_spans(&self) -> &[(SpanRole, Span)] {
+        &self.sub_spans
+    }
+
     /// Returns true if span is successfully added
-    pub fn add_sub_span(&mut self, color: Color, span: Span) -> bool {
+    pub fn add_sub_span(&mut self, role: SpanRole, span: Span) -> bool {
+        let color = role.color();
         let source_map = self.tcx.sess.source_map();
         if let Ok((start_loc, end_loc)) = source_map.is_valid_span(span) {
             // Reports from macros may be in another file and we don't handle them
@@ -149,6 +158,7 @@ impl<'tcx> ColorSpan<'tcx> {
                 col: end_loc.col,
                 id: event_id,
             });
+            self.sub_spans.push((role, span));
             return true;
         } else {
             return false;
@@ -84,6 +84,7 @@ def run_test(test_case):
                 [
                     "rudra",
                     "-Zrudra-enable-unsafe-destructor",
+                    *metadata.get("rudra_args", []),
                     "--crate-type",
                     "lib",
                     test_case.path
@@ -109,6 +110,19 @@ def run_test(test_case):
             )
             assert expected_analyzers == reported_analyzers, analyzer_mismatch_msg
 
+            # Optional: impl headers rewritten by the suggestions of the reports
+            if "expected_suggestions" in metadata:
+                expected_suggestions = set(metadata["expected_suggestions"])
+                reported_suggestions = set(
+                    report["suggestion"]["rewritten"]
+                    for report in reports.get("reports", [])
+                    if "suggestion" in report
+                )
+                suggestion_mismatch_msg = "Suggestion mismatch; expected {}, reported {}".format(
+                    sorted(expected_suggestions), sorted(reported_suggestions)
+                )
+                assert expected_suggestions == reported_suggestions, suggestion_mismatch_msg
+
             return TestResult(test_case, test_type)
     except (AssertionError,) as e:
         return TestResult(test_case, test_type, e)
@@ -0,0 +1,25 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::io::{self, Read};
+
+pub fn read_prefix<'a, R: Read>(reader: &mut R, buf: &'a mut [u8; 64]) -> io::Result<&'a [u8]> {
+    let n = reader.read(buf)?.min(buf.len());
+    Ok(unsafe { std::slice::from_raw_parts(buf.as_ptr(), n) })
+}
+
+pub fn pop_last(vec: &mut Vec<String>) -> Option<String> {
+    let len = vec.len();
+    if len == 0 {
+        return None;
+    }
+    unsafe {
+        vec.set_len(len - 1);
+        Some(std::ptr::read(vec.as_ptr().add(len - 1)))
+    }
+}
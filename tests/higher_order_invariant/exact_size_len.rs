@@ -0,0 +1,22 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["HigherOrderInvariant"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+// `len()` of a safe `ExactSizeIterator` impl can be larger than the number of items
+pub fn collect_exact<I: ExactSizeIterator<Item = u8>>(iter: I) -> Vec<u8> {
+    let len = iter.len();
+    let mut vec = Vec::with_capacity(len);
+    for (i, item) in iter.enumerate() {
+        if i < vec.capacity() {
+            vec.push(item);
+        }
+    }
+    unsafe {
+        vec.set_len(len);
+    }
+    vec
+}
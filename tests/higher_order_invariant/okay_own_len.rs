@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+// `iter.len()` only sizes the allocation; the length set is the vector's own
+pub fn collect_one<I: ExactSizeIterator<Item = u8>>(mut iter: I) -> Vec<u8> {
+    let mut v = Vec::with_capacity(iter.len());
+    if let Some(item) = iter.next() {
+        if v.capacity() > v.len() {
+            unsafe {
+                v.as_mut_ptr().add(v.len()).write(item);
+                v.set_len(v.len() + 1);
+            }
+        }
+    }
+    v
+}
@@ -0,0 +1,15 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["HigherOrderInvariant"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::io::{self, Read};
+
+// A safe `Read` impl can return a byte count larger than the buffer
+pub fn read_prefix<'a, R: Read>(reader: &mut R, buf: &'a mut [u8; 64]) -> io::Result<&'a [u8]> {
+    let n = reader.read(buf)?;
+    Ok(unsafe { std::slice::from_raw_parts(buf.as_ptr(), n) })
+}
@@ -0,0 +1,40 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+pub struct RawBuf<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+// Same as `SetLenOnDrop` in std, but borrows the whole buffer
+struct SetLenOnDrop<'a, T> {
+    buf: &'a mut RawBuf<T>,
+    local_len: usize,
+}
+
+impl<T> Drop for SetLenOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.buf.len = self.local_len;
+    }
+}
+
+impl<T: Clone> RawBuf<T> {
+    // The guard updates the length during unwinding, so cloned items are not leaked or exposed
+    pub fn extend_from_slice(&mut self, items: &[T]) {
+        let mut guard = SetLenOnDrop {
+            local_len: self.len,
+            buf: self,
+        };
+        for item in items {
+            unsafe {
+                guard.buf.ptr.add(guard.local_len).write(item.clone());
+            }
+            guard.local_len += 1;
+        }
+    }
+}
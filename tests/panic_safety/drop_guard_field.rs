@@ -0,0 +1,41 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+pub struct RawBuf<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+// Same as `SetLenOnDrop` in std
+struct SetLenOnDrop<'a> {
+    len: &'a mut usize,
+    local_len: usize,
+}
+
+impl Drop for SetLenOnDrop<'_> {
+    fn drop(&mut self) {
+        *self.len = self.local_len;
+    }
+}
+
+impl<T: Clone> RawBuf<T> {
+    // The guard only writes back to `self.len`, which doesn't cover the writes through `self.ptr`
+    pub fn extend_from_slice(&mut self, items: &[T]) {
+        let ptr = self.ptr;
+        let mut guard = SetLenOnDrop {
+            local_len: self.len,
+            len: &mut self.len,
+        };
+        for item in items {
+            unsafe {
+                ptr.add(guard.local_len).write(item.clone());
+            }
+            guard.local_len += 1;
+        }
+    }
+}
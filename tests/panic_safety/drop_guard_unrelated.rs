@@ -0,0 +1,25 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+struct Counter<'a> {
+    count: &'a mut usize,
+}
+
+impl Drop for Counter<'_> {
+    fn drop(&mut self) {
+        *self.count += 1;
+    }
+}
+
+// The guard does not touch the vector that is bypassed
+pub fn test_drop_guard_unrelated<T, F: FnMut(&T)>(v: &Vec<T>, count: &mut usize, mut f: F) {
+    let _counter = Counter { count };
+    let first = unsafe { std::ptr::read(v.as_ptr()) };
+    f(&first);
+    std::mem::forget(first);
+}
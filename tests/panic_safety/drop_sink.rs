@@ -0,0 +1,11 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// `v` still owns the duplicated element when it is dropped
+pub fn test_drop_sink<T>(v: Vec<T>) -> T {
+    unsafe { std::ptr::read(v.as_ptr()) }
+}
@@ -0,0 +1,13 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// Trait objects run arbitrary user code as well
+pub fn test_dyn_call(v: &Vec<String>, f: &dyn Fn(&String)) {
+    let first = unsafe { std::ptr::read(v.as_ptr()) };
+    f(&first);
+    std::mem::forget(first);
+}
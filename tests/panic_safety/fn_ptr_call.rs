@@ -0,0 +1,13 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// The target of a function pointer is only known at runtime
+pub fn test_fn_ptr_call(v: &Vec<String>, f: fn(&String)) {
+    let first = unsafe { std::ptr::read(v.as_ptr()) };
+    f(&first);
+    std::mem::forget(first);
+}
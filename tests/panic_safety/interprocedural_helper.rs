@@ -0,0 +1,17 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// The lifetime bypass is hidden in a safe helper function
+fn read_first<T>(v: &Vec<T>) -> T {
+    unsafe { std::ptr::read(v.as_ptr()) }
+}
+
+pub fn test_interprocedural<T, F: FnMut(&T)>(v: &Vec<T>, mut f: F) {
+    let first = read_first(v);
+    f(&first);
+    std::mem::forget(first);
+}
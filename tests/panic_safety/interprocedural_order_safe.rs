@@ -0,0 +1,16 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+fn read_first<T>(v: &Vec<T>) -> T {
+    unsafe { std::ptr::read(v.as_ptr()) }
+}
+
+// User code is called before the lifetime bypass in the helper
+pub fn test_interprocedural_order_safe<T, F: FnMut()>(v: &Vec<T>, mut f: F) -> T {
+    f();
+    read_first(v)
+}
@@ -0,0 +1,24 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::mem::{self, MaybeUninit};
+
+// The value is written before `assume_init()`, so `f` never sees uninitialized memory
+pub fn write_then_assume_init<T, F: FnMut(&T)>(value: T, mut f: F) -> T {
+    let mut slot = MaybeUninit::uninit();
+    slot.write(value);
+    let value = unsafe { slot.assume_init() };
+    f(&value);
+    value
+}
+
+// Zeroed bytes are initialized
+pub fn zeroed_counts<F: FnMut(&[u64; 4])>(mut f: F) -> [u64; 4] {
+    let counts: [u64; 4] = unsafe { mem::zeroed() };
+    f(&counts);
+    counts
+}
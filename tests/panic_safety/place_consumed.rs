@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-unsafe-dataflow-mode=place"]
+```
+!*/
+
+use std::ptr;
+
+// The duplicated value is written back before user code runs
+pub fn test_place_consumed<T, F: FnMut(usize)>(v: &mut Vec<T>, mut f: F) {
+    unsafe {
+        let item = ptr::read(v.as_ptr());
+        ptr::write(v.as_mut_ptr(), item);
+    }
+    f(v.len());
+}
@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zrudra-unsafe-dataflow-mode=place"]
+```
+!*/
+
+use std::ptr;
+
+// The duplicated value is still alive while user code runs
+pub fn test_place_live<T, F: FnMut(&T)>(v: &mut Vec<T>, mut f: F) {
+    unsafe {
+        let item = ptr::read(v.as_ptr());
+        f(&item);
+        ptr::write(v.as_mut_ptr(), item);
+    }
+}
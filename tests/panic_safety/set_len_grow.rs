@@ -0,0 +1,15 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// Extending a vector exposes the elements beyond the old length
+pub fn extend_len<T, F: FnMut(&[T])>(v: &mut Vec<T>, f: &mut F) {
+    let len = v.len();
+    unsafe {
+        v.set_len(len + 1);
+    }
+    f(&v[..]);
+}
@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+pub struct Pair<T> {
+    a: Vec<T>,
+    b: Vec<T>,
+}
+
+impl<T> Pair<T> {
+    // The length of `b` says nothing about the initialized elements of `a`
+    pub fn copy_len<F: FnMut(&[T])>(&mut self, mut f: F) {
+        unsafe {
+            self.a.set_len(self.b.len());
+        }
+        f(&self.a);
+    }
+
+    // The slice covers `b` with the length of `a`
+    pub fn mixed_slice<F: FnMut(&[T])>(&self, mut f: F) {
+        let slice = unsafe { std::slice::from_raw_parts(self.b.as_ptr(), self.a.len()) };
+        f(slice);
+    }
+}
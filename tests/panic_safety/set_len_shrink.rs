@@ -0,0 +1,41 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+// Shrinking a vector only leaks the elements
+pub fn truncate_last<T, F: FnMut(&[T])>(v: &mut Vec<T>, f: &mut F) {
+    let len = v.len();
+    if len == 0 {
+        return;
+    }
+    unsafe {
+        v.set_len(len - 1);
+    }
+    f(&v[..]);
+}
+
+// The length is restored to the one observed at the beginning
+pub fn restore_len<T, F: FnMut(&[T])>(v: &mut Vec<T>, f: &mut F) {
+    let old_len = v.len();
+    unsafe {
+        v.set_len(0);
+    }
+    // Elements are leaked if `f` panics
+    f(&v[..]);
+    unsafe {
+        v.set_len(old_len);
+    }
+}
+
+// The slice is within the length of the vector
+pub fn prefix<T, F: FnMut(&[T])>(v: &Vec<T>, f: &mut F) {
+    let len = v.len();
+    if len == 0 {
+        return;
+    }
+    let slice = unsafe { std::slice::from_raw_parts(v.as_ptr(), len - 1) };
+    f(slice);
+}
@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+// `slice::from_raw_parts()` is defined in `core::slice::raw`,
+// so it is only detected with the full def path
+pub fn test_slice_from_raw<T, F: FnMut(&[T])>(v: &Vec<T>, len: usize, mut f: F) {
+    let slice = unsafe { std::slice::from_raw_parts(v.as_ptr(), len) };
+    f(slice);
+}
+
+pub fn test_slice_from_raw_mut<T, F: FnMut(&mut [T])>(v: &mut Vec<T>, len: usize, mut f: F) {
+    let slice = unsafe { std::slice::from_raw_parts_mut(v.as_mut_ptr(), len) };
+    f(slice);
+}
@@ -0,0 +1,17 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+```
+!*/
+
+use std::mem::MaybeUninit;
+
+// Uninitialized value is dropped if `f` panics
+pub fn assume_init_early<T, F: FnMut() -> T>(mut f: F) -> [T; 2] {
+    let mut arr: [T; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    for item in arr.iter_mut() {
+        *item = f();
+    }
+    arr
+}
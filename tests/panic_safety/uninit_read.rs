@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow", "UninitExposure"]
+```
+!*/
+
+use std::io::{self, Read};
+
+// Uninitialized buffer is exposed to a user-provided `Read` implementation
+pub fn read_uninit<R: Read>(reader: &mut R, n: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(n);
+    unsafe {
+        buf.set_len(n);
+    }
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,14 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zrudra-unsafe-dataflow-mode=unwind"]
+```
+!*/
+
+// If `f` panics, the duplicated item is dropped during unwinding
+pub fn test_unwind_drop<T, F: FnMut(&T)>(v: &Vec<T>, mut f: F) {
+    let item = unsafe { std::ptr::read(v.as_ptr()) };
+    f(&item);
+    std::mem::forget(item);
+}
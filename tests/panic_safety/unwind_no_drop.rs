@@ -0,0 +1,15 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-unsafe-dataflow-mode=unwind"]
+```
+!*/
+
+use std::mem::ManuallyDrop;
+
+// The duplicated item is never dropped, even if `f` panics
+pub fn test_unwind_no_drop<T, F: FnMut(&T)>(v: &Vec<T>, mut f: F) {
+    let item = ManuallyDrop::new(unsafe { std::ptr::read(v.as_ptr()) });
+    f(&item);
+}
@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zrudra-unsafe-dataflow-mode=unwind"]
+```
+!*/
+
+// If `clone()` panics, the caller drops uninitialized elements of `v`
+pub fn test_unwind_to_caller<T: Clone>(v: &mut Vec<T>, to_push: &[T]) {
+    v.reserve(to_push.len());
+    unsafe {
+        v.set_len(v.len() + to_push.len());
+        for (i, x) in to_push.iter().enumerate() {
+            v.as_mut_ptr().add(i).write(x.clone());
+        }
+    }
+}
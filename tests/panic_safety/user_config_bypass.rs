@@ -0,0 +1,24 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zrudra-config=tests/panic_safety/user_config.toml"]
+```
+!*/
+
+pub struct Arena<T> {
+    pub slots: Vec<T>,
+}
+
+impl<T> Arena<T> {
+    // In-house primitive that behaves like `ptr::read`
+    pub unsafe fn duplicate(&self, idx: usize) -> T {
+        std::ptr::read(self.slots.as_ptr().add(idx))
+    }
+}
+
+pub fn test_user_bypass<T, F: FnMut(&T)>(arena: &Arena<T>, mut f: F) {
+    let item = unsafe { arena.duplicate(0) };
+    f(&item);
+    std::mem::forget(item);
+}
@@ -0,0 +1,16 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDataflow"]
+rudra_args = ["-Zrudra-config=tests/panic_safety/user_config.toml"]
+```
+!*/
+
+// Runs user-registered hooks, which may panic
+pub fn run_hooks() {}
+
+pub fn test_user_sink<T>(v: &Vec<T>) {
+    let first = unsafe { std::ptr::read(v.as_ptr()) };
+    run_hooks();
+    std::mem::forget(first);
+}
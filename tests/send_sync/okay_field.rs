@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+// The thread safety of raw pointers, `NonNull`, and `UnsafeCell` is decided by the owner
+pub struct Buffer {
+    ptr: *mut u8,
+    head: NonNull<u8>,
+    len: usize,
+}
+
+unsafe impl Send for Buffer {}
+unsafe impl Sync for Buffer {}
+
+pub struct Lock {
+    locked: Arc<Mutex<bool>>,
+    data: UnsafeCell<usize>,
+}
+
+unsafe impl Sync for Lock {}
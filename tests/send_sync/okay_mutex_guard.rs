@@ -0,0 +1,77 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+// Only one thread at a time can hold the guard returned by `lock()`
+pub struct SpinLock<T, U> {
+    data: UnsafeCell<T>,
+    extra: U,
+}
+
+pub struct SpinGuard<'a, T> {
+    ptr: *mut T,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<T, U> SpinLock<T, U> {
+    pub fn new(data: T, extra: U) -> Self {
+        SpinLock {
+            data: UnsafeCell::new(data),
+            extra,
+        }
+    }
+
+    pub fn lock(&self) -> SpinGuard<'_, T> {
+        SpinGuard {
+            ptr: self.data.get(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn extra(&self) -> &U {
+        &self.extra
+    }
+}
+
+impl<'a, T> Deref for SpinGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl<'a, T> DerefMut for SpinGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+unsafe impl<T: Send, U: Sync> Sync for SpinLock<T, U> {}
+
+// The guard of `std::sync::Mutex` is exclusive as well
+pub struct Shared<T, U> {
+    inner: Mutex<T>,
+    extra: U,
+}
+
+impl<T, U> Shared<T, U> {
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.inner.lock().unwrap()
+    }
+
+    pub fn extra(&self) -> &U {
+        &self.extra
+    }
+}
+
+unsafe impl<T: Send, U: Sync> Sync for Shared<T, U> {}
@@ -5,11 +5,45 @@ expected_analyzers = []
 ```
 !*/
 
+use std::ptr::NonNull;
+
 // impl `Send` for `PtrLike<Sync>` is okay
-// Note that we don't check pointer-likeness yet
 
 struct Atom1<P>(P);
 unsafe impl<P: Sync> Send for Atom1<P> {}
 
 struct Atom2<P>(P);
 unsafe impl<P> Send for Atom2<P> where P: Sync {}
+
+// Pointer-like types are okay if `T: Send + Sync`
+pub struct MyArc<T> {
+    ptr: NonNull<T>,
+}
+
+impl<T> MyArc<T> {
+    pub fn get(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for MyArc<T> {
+    fn clone(&self) -> Self {
+        MyArc { ptr: self.ptr }
+    }
+}
+
+unsafe impl<T: Send + Sync> Send for MyArc<T> {}
+
+// Cloning `MyBox` clones `T`, so it is not pointer-like
+#[derive(Clone)]
+pub struct MyBox<T> {
+    data: Box<T>,
+}
+
+impl<T> MyBox<T> {
+    pub fn get(&self) -> &T {
+        &self.data
+    }
+}
+
+unsafe impl<T: Send> Send for MyBox<T> {}
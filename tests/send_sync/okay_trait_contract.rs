@@ -0,0 +1,29 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = [
+    "-Zrudra-config=tests/send_sync/trait_contract.toml",
+    "--crate-name",
+    "trait_contract",
+]
+```
+!*/
+
+use std::marker::PhantomData;
+
+/// Types that can be safely transmuted from any bit pattern
+pub unsafe trait Plain {}
+
+unsafe impl Plain for u8 {}
+unsafe impl Plain for u32 {}
+
+// All fields and the generic parameter are `Plain`
+#[repr(C)]
+pub struct Header<T> {
+    tag: u32,
+    body: T,
+    _marker: PhantomData<String>,
+}
+
+unsafe impl<T: Plain> Plain for Header<T> {}
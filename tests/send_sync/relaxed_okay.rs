@@ -0,0 +1,22 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-send-sync-mode=relaxed"]
+```
+!*/
+
+trait Foo: Sync {}
+trait Bar: Foo {}
+
+// Only one of the generic parameters is bounded
+struct Atom0<P, Q>(P, Q);
+unsafe impl<P: Send, Q> Send for Atom0<P, Q> {}
+
+// `Bar` is `Sync` through `Foo`
+struct Atom1<P>(P);
+unsafe impl<P: Bar> Sync for Atom1<P> {}
+
+// Bound on an associated type
+struct Atom2<P: Iterator>(P, Vec<P::Item>);
+unsafe impl<P: Iterator> Send for Atom2<P> where P::Item: Send {}
@@ -0,0 +1,12 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zrudra-send-sync-mode=relaxed"]
+```
+!*/
+
+// No generic parameter is bounded at all
+struct Atom0<P, Q>(P, Q);
+unsafe impl<P, Q: Eq> Send for Atom0<P, Q> {}
+unsafe impl<P, Q> Sync for Atom0<P, Q> {}
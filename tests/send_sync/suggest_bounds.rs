@@ -0,0 +1,45 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+expected_suggestions = [
+    "unsafe impl<T: Send + Sync> Send for Shared<T>",
+    "unsafe impl<T: Sync> Sync for Shared<T> where T: Clone",
+    "unsafe impl<'a, A: Send, B: Default + Send> Send for Holder<'a, A, B>",
+    "unsafe impl<B: Send> Send for Pair<i32, B>",
+]
+rudra_args = ["-Zsensitivity-low"]
+```
+!*/
+
+use std::marker::PhantomData;
+
+// `T: Send` is already there, `Sync` is added to it
+pub struct Shared<T>(*const T);
+
+impl<T> Shared<T> {
+    pub fn get(&self) -> &T {
+        unsafe { &*self.0 }
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(self.0)
+    }
+}
+
+unsafe impl<T: Send> Send for Shared<T> {}
+
+// The where clause is kept as is
+unsafe impl<T> Sync for Shared<T> where T: Clone {}
+
+// Lifetimes and existing bounds are kept
+pub struct Holder<'a, A, B: Default>(&'a A, B, PhantomData<A>);
+
+unsafe impl<'a, A, B: Default> Send for Holder<'a, A, B> {}
+
+// Concrete arguments don't need bounds
+pub struct Pair<A, B>(A, *const B);
+
+unsafe impl<B> Send for Pair<i32, B> {}
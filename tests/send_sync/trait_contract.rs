@@ -0,0 +1,30 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zrudra-config=tests/send_sync/trait_contract.toml"]
+```
+!*/
+
+/// Types that can be safely transmuted from any bit pattern
+pub unsafe trait Plain {}
+
+unsafe impl Plain for u8 {}
+unsafe impl Plain for u32 {}
+
+// `String` is not `Plain`
+#[repr(C)]
+pub struct Named {
+    id: u32,
+    name: String,
+}
+
+unsafe impl Plain for Named {}
+
+// `T` is not bounded by `Plain`
+#[repr(C)]
+pub struct Wrapper<T> {
+    value: T,
+}
+
+unsafe impl<T> Plain for Wrapper<T> {}
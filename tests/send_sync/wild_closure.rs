@@ -0,0 +1,29 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+// `&T` is exposed to other threads through the closure argument
+pub struct Shared<T, U> {
+    data: T,
+    extra: U,
+}
+
+impl<T, U> Shared<T, U> {
+    pub fn new(data: T, extra: U) -> Self {
+        Shared { data, extra }
+    }
+
+    pub fn with<F: FnOnce(&T)>(&self, f: F) {
+        f(&self.data)
+    }
+
+    pub fn extra(&self) -> &U {
+        &self.extra
+    }
+}
+
+unsafe impl<T: Send, U: Sync> Sync for Shared<T, U> {}
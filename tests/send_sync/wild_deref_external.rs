@@ -0,0 +1,40 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+use std::ops::Deref;
+use std::sync::RwLock;
+
+// The API that exposes `&T` (`RwLock::read()`) comes from the standard library.
+// Readers on several threads share `&T`, so `T: Sync` is needed.
+pub struct Shared<T, U> {
+    lock: RwLock<T>,
+    extra: U,
+}
+
+impl<T, U> Shared<T, U> {
+    pub fn new(data: T, extra: U) -> Self {
+        Shared {
+            lock: RwLock::new(data),
+            extra,
+        }
+    }
+
+    pub fn extra(&self) -> &U {
+        &self.extra
+    }
+}
+
+impl<T, U> Deref for Shared<T, U> {
+    type Target = RwLock<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lock
+    }
+}
+
+unsafe impl<T: Send, U: Sync> Sync for Shared<T, U> {}
@@ -0,0 +1,35 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+```
+!*/
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::MutexGuard;
+
+// `Rc` is `!Send`, even behind a `Vec`
+pub struct Handle {
+    shared: Vec<Rc<u8>>,
+}
+
+unsafe impl Send for Handle {}
+
+// `Cell` is `!Sync`
+pub struct Counter {
+    hits: Cell<usize>,
+}
+
+unsafe impl Sync for Counter {}
+
+// `MutexGuard` is `!Send`, even within another ADT
+pub struct Locked<'a> {
+    inner: Inner<'a>,
+}
+
+pub struct Inner<'a> {
+    guard: MutexGuard<'a, u8>,
+}
+
+unsafe impl<'a> Send for Locked<'a> {}
@@ -0,0 +1,52 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+rudra_args = ["-Zsensitivity-med"]
+```
+!*/
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+// `&T` is exposed to other threads through the guard returned by `read()`
+pub struct ReadLock<T, U> {
+    data: UnsafeCell<T>,
+    extra: U,
+}
+
+pub struct ReadGuard<'a, T> {
+    ptr: *const T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<T, U> ReadLock<T, U> {
+    pub fn new(data: T, extra: U) -> Self {
+        ReadLock {
+            data: UnsafeCell::new(data),
+            extra,
+        }
+    }
+
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        ReadGuard {
+            ptr: self.data.get(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn extra(&self) -> &U {
+        &self.extra
+    }
+}
+
+impl<'a, T> Deref for ReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+unsafe impl<T: Send, U: Sync> Sync for ReadLock<T, U> {}
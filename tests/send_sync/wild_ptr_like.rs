@@ -0,0 +1,46 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["SendSyncVariance"]
+```
+!*/
+
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Inner<T> {
+    count: AtomicUsize,
+    data: T,
+}
+
+// Shared pointer that exposes `&T` to every clone
+pub struct MyArc<T> {
+    ptr: NonNull<Inner<T>>,
+}
+
+impl<T> MyArc<T> {
+    pub fn new(data: T) -> Self {
+        let inner = Box::new(Inner {
+            count: AtomicUsize::new(1),
+            data,
+        });
+        MyArc {
+            ptr: NonNull::from(Box::leak(inner)),
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        unsafe { &self.ptr.as_ref().data }
+    }
+}
+
+impl<T> Clone for MyArc<T> {
+    fn clone(&self) -> Self {
+        unsafe { self.ptr.as_ref() }.count.fetch_add(1, Ordering::Relaxed);
+        MyArc { ptr: self.ptr }
+    }
+}
+
+// `MyArc<Cell<_>>` can be sent to another thread while a clone stays
+unsafe impl<T: Send> Send for MyArc<T> {}
+unsafe impl<T: Send + Sync> Sync for MyArc<T> {}
@@ -0,0 +1,19 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UninitExposure"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::io::{self, BufRead};
+use std::mem;
+
+// Trait objects are user-provided as well
+pub fn read_line_prefix(reader: &mut dyn BufRead) -> io::Result<[u8; 16]> {
+    #[allow(deprecated)]
+    let mut prefix: [u8; 16] = unsafe { mem::uninitialized() };
+    let len = reader.fill_buf()?.len().min(16);
+    reader.read(&mut prefix[..len])?;
+    Ok(prefix)
+}
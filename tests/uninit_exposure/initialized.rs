@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::io::{self, Read};
+use std::mem;
+
+pub fn read_zeroed<R: Read>(reader: &mut R) -> io::Result<[u8; 16]> {
+    // Zeroed bytes are initialized
+    let mut buf: [u8; 16] = unsafe { mem::zeroed() };
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+pub fn read_resized<R: Read>(reader: &mut R, n: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(n);
+    buf.resize(n, 0);
+    unsafe {
+        buf.set_len(n);
+    }
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,18 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UninitExposure"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::io::{self, Read};
+
+pub fn read_exact_uninit<R: Read>(reader: &mut R, n: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(n);
+    unsafe {
+        buf.set_len(n);
+    }
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,21 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDestructor"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::ptr;
+
+pub struct Callback<F: FnOnce()> {
+    callback: F,
+}
+
+// `callback` is dropped again after `drop()` returns
+impl<F: FnOnce()> Drop for Callback<F> {
+    fn drop(&mut self) {
+        let callback = unsafe { ptr::read(&self.callback) };
+        callback();
+    }
+}
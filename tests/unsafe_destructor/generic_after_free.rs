@@ -0,0 +1,27 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDestructor"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::alloc::{dealloc, Layout};
+use std::ptr;
+
+pub struct RawBuf<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+// Elements are dropped after their buffer is freed
+impl<T> Drop for RawBuf<T> {
+    fn drop(&mut self) {
+        unsafe {
+            dealloc(self.ptr as *mut u8, Layout::array::<T>(self.len).unwrap());
+            for i in 0..self.len {
+                ptr::drop_in_place(self.ptr.add(i));
+            }
+        }
+    }
+}
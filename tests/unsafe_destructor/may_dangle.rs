@@ -0,0 +1,33 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDestructor"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+#![feature(dropck_eyepatch)]
+
+use std::fmt::Display;
+
+pub struct Logged<T: Display> {
+    value: T,
+}
+
+// `T` may be dangling, but it is formatted
+unsafe impl<#[may_dangle] T: Display> Drop for Logged<T> {
+    fn drop(&mut self) {
+        println!("dropping {}", self.value);
+    }
+}
+
+pub struct Snapshot<T: Clone> {
+    values: Vec<T>,
+}
+
+// `T` may be dangling, but it is cloned through `Vec<T>`
+unsafe impl<#[may_dangle] T: Clone> Drop for Snapshot<T> {
+    fn drop(&mut self) {
+        let last = self.values.clone();
+        std::mem::forget(last);
+    }
+}
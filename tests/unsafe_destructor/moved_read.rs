@@ -0,0 +1,37 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnsafeDestructor"]
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::{mem, ptr};
+
+pub struct Pool {
+    buffers: Vec<Vec<u8>>,
+    on_release: fn(usize),
+}
+
+// `buffers` is freed and then read, even though it is written back later
+impl Drop for Pool {
+    fn drop(&mut self) {
+        let buffers = unsafe { ptr::read(&self.buffers) };
+        drop(buffers);
+        (self.on_release)(self.buffers.len());
+        unsafe { ptr::write(&mut self.buffers, Vec::new()) };
+    }
+}
+
+pub struct Slot {
+    value: String,
+}
+
+// `value` is dropped while uninitialized
+#[allow(deprecated, invalid_value)]
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let value = mem::replace(&mut self.value, unsafe { mem::uninitialized() });
+        println!("{}", value);
+    }
+}
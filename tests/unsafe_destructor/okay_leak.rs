@@ -1,7 +1,7 @@
 /*!
 ```rudra-test
-test_type = "fp"
-expected_analyzers = ["UnsafeDestructor"]
+test_type = "normal"
+expected_analyzers = []
 ```
 !*/
 
@@ -9,7 +9,7 @@ pub struct Leak<'a> {
     vec: &'a mut Vec<u32>,
 }
 
-// calling an actual unsafe function, needs developer triage
+// calling an actual unsafe function is not a problem by itself
 // this case, memory is leaked but it is not UB
 impl Drop for Leak<'_> {
     fn drop(&mut self) {
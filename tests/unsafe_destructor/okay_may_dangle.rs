@@ -0,0 +1,52 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+#![feature(dropck_eyepatch)]
+
+use std::fmt::Display;
+use std::ptr;
+
+pub struct RawVec<T> {
+    ptr: *mut T,
+    len: usize,
+    cap: usize,
+}
+
+// `T` is only dropped
+unsafe impl<#[may_dangle] T> Drop for RawVec<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr, self.len));
+            drop(Vec::from_raw_parts(self.ptr, 0, self.cap));
+        }
+    }
+}
+
+pub struct Counted<T: Display> {
+    values: Vec<T>,
+}
+
+// Only the length of the vector is used
+unsafe impl<#[may_dangle] T: Display> Drop for Counted<T> {
+    fn drop(&mut self) {
+        println!("dropping {} values", self.values.len());
+        for value in self.values.drain(..) {
+            drop(value);
+        }
+    }
+}
+
+pub struct Shown<T: Display> {
+    value: T,
+}
+
+// Not `#[may_dangle]`, so formatting `T` is fine
+impl<T: Display> Drop for Shown<T> {
+    fn drop(&mut self) {
+        println!("dropping {}", self.value);
+    }
+}
@@ -0,0 +1,65 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+rudra_args = ["-Zrudra-disable-unsafe-dataflow"]
+```
+!*/
+
+use std::alloc::{dealloc, Layout};
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+
+pub struct Restore<T> {
+    value: Option<T>,
+}
+
+// The field is written back before it is used or dropped again
+impl<T> Drop for Restore<T> {
+    fn drop(&mut self) {
+        let value = unsafe { ptr::read(&self.value) };
+        unsafe { ptr::write(&mut self.value, None) };
+        drop(value);
+    }
+}
+
+pub struct Forget {
+    value: Vec<u8>,
+}
+
+// The moved value is forgotten, so the field is dropped only once
+impl Drop for Forget {
+    fn drop(&mut self) {
+        let value = unsafe { ptr::read(&self.value) };
+        println!("{}", value.len());
+        mem::forget(value);
+    }
+}
+
+pub struct Manual {
+    value: ManuallyDrop<String>,
+}
+
+impl Drop for Manual {
+    fn drop(&mut self) {
+        let value = unsafe { ptr::read(&*self.value) };
+        drop(value);
+    }
+}
+
+pub struct RawBuf<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+// Elements are dropped before their buffer is freed
+impl<T> Drop for RawBuf<T> {
+    fn drop(&mut self) {
+        unsafe {
+            for i in 0..self.len {
+                ptr::drop_in_place(self.ptr.add(i));
+            }
+            dealloc(self.ptr as *mut u8, Layout::array::<T>(self.len).unwrap());
+        }
+    }
+}
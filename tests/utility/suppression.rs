@@ -0,0 +1,41 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+use std::ptr;
+
+pub struct Reviewed<T>(T);
+
+// The impl is reviewed: `Reviewed` is only used with `Send` types
+#[cfg_attr(rudra, rudra::allow(send_sync_variance))]
+unsafe impl<T> Send for Reviewed<T> {}
+
+#[cfg_attr(rudra, rudra::allow(unsafe_dataflow))]
+pub fn duplicate<T>(val: &T, f: impl Fn()) -> T {
+    let copy = unsafe { ptr::read(val) };
+    f();
+    copy
+}
+
+// Suppressions apply to nested items
+#[cfg_attr(rudra, rudra::allow(all))]
+pub mod reviewed {
+    use std::ptr;
+
+    pub struct Pair<T>(pub T, pub T);
+
+    unsafe impl<T> Sync for Pair<T> {}
+
+    impl<T> Pair<T> {
+        pub fn swap_with(&mut self, f: impl Fn(&T)) {
+            unsafe {
+                let first = ptr::read(&self.0);
+                f(&first);
+                ptr::write(&mut self.0, first);
+            }
+        }
+    }
+}
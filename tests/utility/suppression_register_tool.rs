@@ -0,0 +1,15 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = []
+```
+!*/
+
+// The crate registers `rudra` itself, so Rudra must not inject it again
+#![feature(register_tool)]
+#![register_tool(rudra)]
+
+pub struct Reviewed<T>(T);
+
+#[cfg_attr(rudra, rudra::allow(send_sync_variance))]
+unsafe impl<T> Send for Reviewed<T> {}
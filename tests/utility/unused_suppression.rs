@@ -0,0 +1,17 @@
+/*!
+```rudra-test
+test_type = "normal"
+expected_analyzers = ["UnusedSuppression"]
+```
+!*/
+
+pub struct Safe<T>(T);
+
+// `Safe<T>` is `Send` if `T: Send`, so there's nothing to suppress
+#[cfg_attr(rudra, rudra::allow(send_sync_variance))]
+unsafe impl<T: Send> Send for Safe<T> {}
+
+#[cfg_attr(rudra, rudra::allow(unsafe_dataflow))]
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}